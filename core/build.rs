@@ -0,0 +1,181 @@
+//! Validate the embedded data files and compile them into static tables,
+//! so that the app does not parse JSON at runtime. See `src/data.rs` for the consumer.
+
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::fmt::{Display, Write};
+use std::fs;
+use std::path::Path;
+
+// The same parsers as runtime datasets, of which only a part is used here
+#[allow(dead_code)]
+#[path = "src/data/schema.rs"]
+mod schema;
+
+use schema::{
+    parse_family_meta_json, parse_id_name_json, parse_lang_json, parse_meta_json,
+    parse_monster_json, BodySize, DataError, GameData, LocalizedName, Monster, MonsterId,
+};
+
+const DATA_DIR: &str = "src/data";
+
+fn load<T>(file: &str, parse: impl Fn(&str, &str) -> Result<T, DataError>) -> T {
+    let path = Path::new(DATA_DIR).join(file);
+    println!("cargo:rerun-if-changed={:}", path.display());
+    let text = fs::read_to_string(&path).unwrap_or_else(|e| panic!("{:}: {:}", path.display(), e));
+    parse(file, &text).unwrap_or_else(|e| panic!("{:}/{:}", DATA_DIR, e))
+}
+
+fn sorted<K: Ord, V>(table: &HashMap<K, V>) -> BTreeMap<&K, &V> {
+    table.iter().collect()
+}
+
+fn write_name_table<K: Ord + Display>(
+    out: &mut String,
+    name: &str,
+    table: &HashMap<K, LocalizedName>,
+) {
+    writeln!(out, "static {:}: &[(usize, &str, Option<&str>)] = &[", name).unwrap();
+    for (id, name) in sorted(table) {
+        writeln!(
+            out,
+            "    ({:}, {:?}, {:?}),",
+            id,
+            name.ja,
+            name.en.as_deref()
+        )
+        .unwrap();
+    }
+    writeln!(out, "];").unwrap();
+}
+
+fn write_family_meta_table(out: &mut String, data: &GameData) {
+    writeln!(
+        out,
+        "static FAMILY_META_TABLE: &[(usize, &str, &str, &str)] = &["
+    )
+    .unwrap();
+    for (id, meta) in sorted(&data.family_meta) {
+        writeln!(
+            out,
+            "    ({:}, {:?}, {:?}, {:?}),",
+            id, meta.slug, meta.color, meta.icon
+        )
+        .unwrap();
+    }
+    writeln!(out, "];").unwrap();
+}
+
+fn write_lang_table(out: &mut String, data: &GameData) {
+    writeln!(out, "static LANG_TABLE: &[(&str, &str, &str)] = &[").unwrap();
+    for (key, text) in sorted(&data.lang) {
+        writeln!(out, "    ({:?}, {:?}, {:?}),", key, text.ja, text.en).unwrap();
+    }
+    writeln!(out, "];").unwrap();
+}
+
+fn write_base_meta(out: &mut String, data: &GameData) {
+    writeln!(
+        out,
+        "static BASE_META: (&str, Option<&str>, Option<&str>, Option<&str>) = ({:?}, {:?}, {:?}, {:?});",
+        data.meta.name.ja,
+        data.meta.name.en.as_deref(),
+        data.meta.game_version.as_deref(),
+        data.meta.source_date.as_deref()
+    )
+    .unwrap();
+}
+
+fn names_record(names: &[LocalizedName]) -> String {
+    names
+        .iter()
+        .map(|name| format!("({:?}, {:?}), ", name.ja, name.en.as_deref()))
+        .collect()
+}
+
+fn monster_record(id: &MonsterId, monster: &Monster) -> String {
+    let mut parents = String::new();
+    for recipe in monster.parents.iter() {
+        parents.push_str("&[");
+        for parent in recipe.iter() {
+            write!(
+                parents,
+                "({:?}, {:?}), ",
+                parent.monster.map(|m| m.0),
+                parent.family.map(|f| f.0)
+            )
+            .unwrap();
+        }
+        parents.push_str("], ");
+    }
+
+    let mut habitats = String::new();
+    for (area, habitat) in sorted(&monster.habitats) {
+        write!(habitats, "({:}, &[", area).unwrap();
+        for (season, flags) in sorted(&habitat.seasons) {
+            write!(habitats, "({:}, &{:?}), ", season, flags.flags()).unwrap();
+        }
+        habitats.push_str("]), ");
+    }
+
+    let details = &monster.details;
+    let size = match details.size {
+        None => "None",
+        Some(BodySize::Normal) => "Some(BodySize::Normal)",
+        Some(BodySize::Large) => "Some(BodySize::Large)",
+        Some(BodySize::Giant) => "Some(BodySize::Giant)",
+    };
+    format!(
+        "MonsterRecord {{ id: {:}, name: ({:?}, {:?}), rank: {:}, family: {:}, parents: &[{:}], habitats: &[{:}], stats: {:?}, size: {:}, traits: &[{:}], skills: &[{:}] }}",
+        id,
+        monster.name.ja,
+        monster.name.en.as_deref(),
+        monster.rank,
+        monster.family,
+        parents,
+        habitats,
+        details.stats,
+        size,
+        names_record(&details.traits),
+        names_record(&details.skills)
+    )
+}
+
+fn write_monster_table(out: &mut String, data: &GameData) {
+    writeln!(out, "static MONSTER_TABLE: &[MonsterRecord] = &[").unwrap();
+    for (id, monster) in sorted(&data.monsters) {
+        writeln!(out, "    {:},", monster_record(id, monster)).unwrap();
+    }
+    writeln!(out, "];").unwrap();
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=src/data/schema.rs");
+
+    let data = GameData {
+        meta: load("meta.json", parse_meta_json),
+        monsters: load("monster.json", parse_monster_json),
+        families: load("family.json", parse_id_name_json),
+        family_meta: load("family.json", parse_family_meta_json),
+        ranks: load("rank.json", parse_id_name_json),
+        areas: load("area.json", parse_id_name_json),
+        seasons: load("season.json", parse_id_name_json),
+        lang: load("lang.json", parse_lang_json),
+    };
+    data.check()
+        .unwrap_or_else(|e| panic!("{:}/{:}", DATA_DIR, e));
+
+    let mut out = String::new();
+    write_name_table(&mut out, "AREA_TABLE", &data.areas);
+    write_name_table(&mut out, "FAMILY_TABLE", &data.families);
+    write_name_table(&mut out, "RANK_TABLE", &data.ranks);
+    write_name_table(&mut out, "SEASON_TABLE", &data.seasons);
+    write_family_meta_table(&mut out, &data);
+    write_lang_table(&mut out, &data);
+    write_base_meta(&mut out, &data);
+    write_monster_table(&mut out, &data);
+
+    let path = Path::new(&env::var("OUT_DIR").unwrap()).join("tables.rs");
+    fs::write(path, out).unwrap();
+}
@@ -0,0 +1,59 @@
+//! Compare two versions of `monster.json` before accepting a data correction.
+//!
+//! Usage: `cargo run -p dqm3_fusion_core --bin diff_dataset -- <old monster.json> <new monster.json> [--locale ja|en]`
+//!
+//! Prints the added, removed and changed monsters and the recipe trees that change.
+//! Exits with 1 when the files differ, like `diff`.
+
+use std::collections::HashMap;
+use std::fs;
+use std::process::ExitCode;
+
+use dqm3_fusion_core::data::{load_monster_data, GameData, Locale, Monster, MonsterId};
+use dqm3_fusion_core::diff;
+
+const USAGE: &str = "usage: diff_dataset <old monster.json> <new monster.json> [--locale ja|en]";
+
+fn load(path: &str, tables: &GameData) -> Result<HashMap<MonsterId, Monster>, String> {
+    let json = fs::read_to_string(path).map_err(|e| format!("{:}: {:}", path, e))?;
+    load_monster_data(path, &json, tables).map_err(|e| e.to_string())
+}
+
+fn run() -> Result<bool, String> {
+    let mut paths = vec![];
+    let mut locale = Locale::default();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--locale" => {
+                locale = args
+                    .next()
+                    .and_then(|code| Locale::from_code(&code))
+                    .ok_or(USAGE)?
+            }
+            _ if !arg.starts_with("--") => paths.push(arg),
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+    let [old_path, new_path] = &paths[..] else {
+        return Err(USAGE.to_string());
+    };
+
+    let tables = GameData::embedded().map_err(|e| e.to_string())?;
+    let old = load(old_path, tables)?;
+    let new = load(new_path, tables)?;
+    let diff = diff::diff_monsters(&old, &new);
+    print!("{:}", diff::to_report(&diff, &old, &new, tables, locale));
+    Ok(diff.is_empty())
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(1),
+        Err(message) => {
+            eprintln!("{:}", message);
+            ExitCode::from(2)
+        }
+    }
+}
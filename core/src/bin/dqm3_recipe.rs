@@ -0,0 +1,176 @@
+//! Look up recipe trees and fusion materials from the terminal.
+//!
+//! Usage: `cargo run -p dqm3_fusion_core --bin dqm3-recipe -- <command> <name|query> [options]`
+//!
+//! Commands:
+//! - `tree <name>`: the recipe tree of the monster
+//! - `materials <name>`: the materials to prepare for the recipe tree
+//! - `used-in <name>`: the monsters with a recipe that takes the monster as a parent
+//! - `search <query>`: the monsters matching the query, best matches first
+//!
+//! Names must match exactly in either locale, ignoring kana, width and long vowel marks.
+//! Otherwise the command fails and the closest names are suggested.
+//! `--prefer-standard` prefers the standard fusion over the special one, `--json` prints JSON
+//! instead of text, `--locale ja|en` picks the language and `--limit N` caps the search results.
+//! In the JSON output, each monster carries its `id`, which family nodes and materials lack.
+
+use std::cell::RefCell;
+use std::process::ExitCode;
+use std::rc::Rc;
+
+use unicode_width::UnicodeWidthStr;
+
+use dqm3_fusion_core::data::{GameData, Locale, MonsterId};
+use dqm3_fusion_core::export::{
+    get_monster_rows, materials_to_json, materials_to_text, to_json_tree, to_text_tree, MonsterRow,
+};
+use dqm3_fusion_core::recipe::{used_in, MonsterTreeBuilder};
+use dqm3_fusion_core::search::SearchIndex;
+
+const USAGE: &str = "usage: dqm3-recipe <tree|materials|used-in|search> <name|query> \
+                     [--prefer-standard] [--json] [--locale ja|en] [--limit N]";
+
+const DEFAULT_SEARCH_LIMIT: usize = 10;
+const SUGGESTION_LIMIT: usize = 3;
+
+enum Command {
+    Tree,
+    Materials,
+    UsedIn,
+    Search,
+}
+
+struct Args {
+    command: Command,
+    query: String,
+    prefer_standard: bool,
+    json: bool,
+    locale: Locale,
+    limit: usize,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut words = vec![];
+    let mut prefer_standard = false;
+    let mut json = false;
+    let mut locale = Locale::default();
+    let mut limit = DEFAULT_SEARCH_LIMIT;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--prefer-standard" => prefer_standard = true,
+            "--json" => json = true,
+            "--locale" => {
+                locale = args
+                    .next()
+                    .and_then(|code| Locale::from_code(&code))
+                    .ok_or(USAGE)?
+            }
+            "--limit" => limit = args.next().and_then(|n| n.parse().ok()).ok_or(USAGE)?,
+            _ if !arg.starts_with("--") => words.push(arg),
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+    let [command, query] = &words[..] else {
+        return Err(USAGE.to_string());
+    };
+    let command = match command.as_str() {
+        "tree" => Command::Tree,
+        "materials" => Command::Materials,
+        "used-in" => Command::UsedIn,
+        "search" => Command::Search,
+        _ => return Err(USAGE.to_string()),
+    };
+
+    Ok(Args {
+        command,
+        query: query.clone(),
+        prefer_standard,
+        json,
+        locale,
+        limit,
+    })
+}
+
+fn monsters_to_text(rows: &[MonsterRow]) -> String {
+    let width = rows.iter().map(|row| row.name.width()).max().unwrap_or(0);
+    rows.iter()
+        .map(|row| {
+            let padding = " ".repeat(width - row.name.width());
+            format!(
+                "{:}{:}  {:}  {:}\n",
+                row.name, padding, row.rank, row.family
+            )
+        })
+        .collect()
+}
+
+fn run() -> Result<(), String> {
+    let args = parse_args()?;
+    let data = GameData::embedded().map_err(|e| e.to_string())?;
+    let index = SearchIndex::new(&data.monsters);
+
+    if let Command::Search = args.command {
+        let ids = index
+            .search(&args.query, args.limit)
+            .into_iter()
+            .map(|hit| hit.monster)
+            .collect::<Vec<MonsterId>>();
+        let rows = get_monster_rows(&ids, data, args.locale);
+        match args.json {
+            true => println!("{:}", serde_json::to_string_pretty(&rows).unwrap()),
+            false => print!("{:}", monsters_to_text(&rows)),
+        }
+        return Ok(());
+    }
+
+    let monster_id = index.resolve(&args.query).ok_or_else(|| {
+        let suggestions = index
+            .search(&args.query, SUGGESTION_LIMIT)
+            .iter()
+            .map(|hit| {
+                data.monsters[&hit.monster]
+                    .name
+                    .get(args.locale)
+                    .to_string()
+            })
+            .collect::<Vec<String>>();
+        match suggestions.is_empty() {
+            true => format!("no monster is named \"{:}\"", args.query),
+            false => format!(
+                "no monster is named \"{:}\", did you mean {:}?",
+                args.query,
+                suggestions.join(", ")
+            ),
+        }
+    })?;
+    if let Command::UsedIn = args.command {
+        let rows = get_monster_rows(&used_in(&data.monsters, monster_id), data, args.locale);
+        match args.json {
+            true => println!("{:}", serde_json::to_string_pretty(&rows).unwrap()),
+            false => print!("{:}", monsters_to_text(&rows)),
+        }
+        return Ok(());
+    }
+
+    let mut tree_builder = MonsterTreeBuilder::new(data.monsters.clone());
+    tree_builder.prefer_standard_fusion(args.prefer_standard);
+    let tree = Rc::new(RefCell::new(tree_builder.build(monster_id)));
+    match (args.command, args.json) {
+        (Command::Tree, true) => println!("{:}", to_json_tree(tree, data, args.locale)),
+        (Command::Tree, false) => print!("{:}", to_text_tree(tree, data, args.locale)),
+        (_, true) => println!("{:}", materials_to_json(tree, data, args.locale)),
+        (_, false) => print!("{:}", materials_to_text(tree, data, args.locale)),
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("{:}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
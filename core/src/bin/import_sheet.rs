@@ -0,0 +1,151 @@
+//! Convert the shared spreadsheet, exported as CSV or TSV, into `monster.json` and `area.json`.
+//! See `core/src/import.rs` for the columns.
+//!
+//! Usage: `cargo run -p dqm3_fusion_core --features sheet --bin import_sheet -- <sheet.csv|sheet.tsv> [--data-dir DIR] [--out-dir DIR]`
+//!
+//! The sheet is resolved against the data files in `--data-dir` (`core/src/data` by default),
+//! and the files are written to `--out-dir` (the data directory by default).
+//! When a row cannot be resolved, every problem is reported and nothing is written.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use dqm3_fusion_core::data::{
+    parse_id_name_json, parse_monster_json, AreaId, FamilyId, RankId, SeasonId,
+};
+use dqm3_fusion_core::import::{import_rows, to_data_json, ImportContext};
+
+struct Args {
+    sheet: PathBuf,
+    data_dir: PathBuf,
+    out_dir: Option<PathBuf>,
+}
+
+const USAGE: &str = "usage: import_sheet <sheet.csv|sheet.tsv> [--data-dir DIR] [--out-dir DIR]";
+
+fn parse_args() -> Result<Args, String> {
+    let mut sheet = None;
+    let mut data_dir = PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/src/data"));
+    let mut out_dir = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--data-dir" => data_dir = args.next().ok_or(USAGE)?.into(),
+            "--out-dir" => out_dir = Some(args.next().ok_or(USAGE)?.into()),
+            _ if sheet.is_none() && !arg.starts_with("--") => sheet = Some(arg.into()),
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+    Ok(Args {
+        sheet: sheet.ok_or(USAGE)?,
+        data_dir,
+        out_dir,
+    })
+}
+
+fn read_file(dir: &Path, file: &str) -> Result<String, String> {
+    let path = dir.join(file);
+    fs::read_to_string(&path).map_err(|e| format!("{:}: {:}", path.display(), e))
+}
+
+fn load_context(dir: &Path) -> Result<ImportContext, String> {
+    let table = |file: &str| read_file(dir, file).map(|json| (file.to_string(), json));
+    let (file, json) = table("monster.json")?;
+    let monsters = parse_monster_json(&file, &json).map_err(|e| e.to_string())?;
+    let (file, json) = table("family.json")?;
+    let families = parse_id_name_json::<FamilyId>(&file, &json).map_err(|e| e.to_string())?;
+    let (file, json) = table("rank.json")?;
+    let ranks = parse_id_name_json::<RankId>(&file, &json).map_err(|e| e.to_string())?;
+    let (file, json) = table("area.json")?;
+    let areas = parse_id_name_json::<AreaId>(&file, &json).map_err(|e| e.to_string())?;
+    let (file, json) = table("season.json")?;
+    let seasons = parse_id_name_json::<SeasonId>(&file, &json).map_err(|e| e.to_string())?;
+    Ok(ImportContext {
+        monsters,
+        families,
+        ranks,
+        areas,
+        seasons,
+    })
+}
+
+/// TSV is recognized by the extension.
+fn read_sheet(path: &Path) -> Result<(Vec<String>, Vec<Vec<String>>), String> {
+    let delimiter = match path.extension().and_then(|e| e.to_str()) {
+        Some("tsv") => b'\t',
+        _ => b',',
+    };
+    let error = |e: csv::Error| format!("{:}: {:}", path.display(), e);
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .flexible(true)
+        .from_path(path)
+        .map_err(error)?;
+    let header = reader
+        .headers()
+        .map_err(error)?
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    let rows = reader
+        .records()
+        .map(|record| record.map(|r| r.iter().map(|s| s.to_string()).collect()))
+        .collect::<Result<Vec<Vec<String>>, csv::Error>>()
+        .map_err(error)?;
+    Ok((header, rows))
+}
+
+fn run(args: Args) -> Result<(), String> {
+    let context = load_context(&args.data_dir)?;
+    let (header, rows) = read_sheet(&args.sheet)?;
+    let result = match import_rows(&header, &rows, &context) {
+        Ok(result) => result,
+        Err(issues) => {
+            for issue in issues.iter() {
+                eprintln!("{:}: {:}", args.sheet.display(), issue);
+            }
+            let mut rows = issues.iter().map(|i| i.row).collect::<Vec<usize>>();
+            rows.dedup();
+            return Err(format!(
+                "{:} rows could not be resolved, nothing was written",
+                rows.len()
+            ));
+        }
+    };
+
+    for area in result.new_areas.iter() {
+        eprintln!("new area {:}: {:}", area.0, result.areas[area].ja);
+    }
+    let out_dir = args.out_dir.as_ref().unwrap_or(&args.data_dir);
+    let write = |file: &str, json: String| {
+        let path = out_dir.join(file);
+        fs::write(&path, json).map_err(|e| format!("{:}: {:}", path.display(), e))
+    };
+    write("monster.json", to_data_json(&result.monsters))?;
+    write("area.json", to_data_json(&result.areas))?;
+
+    let kept = result
+        .monsters
+        .keys()
+        .filter(|id| context.monsters.contains_key(id))
+        .count();
+    eprintln!(
+        "{:} monsters ({:} kept their id, {:} new), {:} areas",
+        result.monsters.len(),
+        kept,
+        result.monsters.len() - kept,
+        result.areas.len()
+    );
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match parse_args().and_then(run) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("{:}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
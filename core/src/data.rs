@@ -0,0 +1,684 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::OnceLock;
+
+mod schema;
+
+pub use schema::*;
+
+/// `(ja, en)` of a name in the generated tables.
+type NameRecord = (&'static str, Option<&'static str>);
+/// `(monster, family)` of a parent in the generated tables.
+type ParentRecord = (Option<usize>, Option<usize>);
+/// `(area, [(season, flags)])` in the generated tables.
+type HabitatRecord = (usize, &'static [(usize, &'static [bool])]);
+
+struct MonsterRecord {
+    id: usize,
+    name: NameRecord,
+    rank: usize,
+    family: usize,
+    parents: &'static [&'static [ParentRecord]],
+    habitats: &'static [HabitatRecord],
+    stats: Option<BaseStats>,
+    size: Option<BodySize>,
+    traits: &'static [NameRecord],
+    skills: &'static [NameRecord],
+}
+
+// The data files are validated and compiled into static tables at build time
+include!(concat!(env!("OUT_DIR"), "/tables.rs"));
+
+fn name_record(&(ja, en): &NameRecord) -> LocalizedName {
+    LocalizedName {
+        ja: ja.to_string(),
+        en: en.map(|en| en.to_string()),
+    }
+}
+
+fn name_table<K: From<usize> + Hash + Eq>(
+    table: &[(usize, &str, Option<&str>)],
+) -> HashMap<K, LocalizedName> {
+    table
+        .iter()
+        .map(|&(id, ja, en)| {
+            let name = LocalizedName {
+                ja: ja.to_string(),
+                en: en.map(|en| en.to_string()),
+            };
+            (K::from(id), name)
+        })
+        .collect()
+}
+
+pub fn get_area_data() -> &'static HashMap<AreaId, LocalizedName> {
+    static DATA: OnceLock<HashMap<AreaId, LocalizedName>> = OnceLock::new();
+    DATA.get_or_init(|| name_table(AREA_TABLE))
+}
+
+pub fn get_family_data() -> &'static HashMap<FamilyId, LocalizedName> {
+    static DATA: OnceLock<HashMap<FamilyId, LocalizedName>> = OnceLock::new();
+    DATA.get_or_init(|| name_table(FAMILY_TABLE))
+}
+
+pub fn get_family_meta() -> &'static HashMap<FamilyId, FamilyMeta> {
+    static DATA: OnceLock<HashMap<FamilyId, FamilyMeta>> = OnceLock::new();
+    DATA.get_or_init(|| {
+        FAMILY_META_TABLE
+            .iter()
+            .map(|&(id, slug, color, icon)| {
+                let meta = FamilyMeta {
+                    slug: slug.to_string(),
+                    color: color.to_string(),
+                    icon: icon.to_string(),
+                };
+                (FamilyId(id), meta)
+            })
+            .collect()
+    })
+}
+
+pub fn get_rank_data() -> &'static HashMap<RankId, LocalizedName> {
+    static DATA: OnceLock<HashMap<RankId, LocalizedName>> = OnceLock::new();
+    DATA.get_or_init(|| name_table(RANK_TABLE))
+}
+
+pub fn get_season_data() -> &'static HashMap<SeasonId, LocalizedName> {
+    static DATA: OnceLock<HashMap<SeasonId, LocalizedName>> = OnceLock::new();
+    DATA.get_or_init(|| name_table(SEASON_TABLE))
+}
+
+pub fn get_monster_data() -> &'static HashMap<MonsterId, Monster> {
+    static DATA: OnceLock<HashMap<MonsterId, Monster>> = OnceLock::new();
+    DATA.get_or_init(|| MONSTER_TABLE.iter().map(monster_record).collect())
+}
+
+fn monster_record(record: &MonsterRecord) -> (MonsterId, Monster) {
+    let monster = Monster {
+        name: name_record(&record.name),
+        rank: RankId(record.rank),
+        family: FamilyId(record.family),
+        parents: record
+            .parents
+            .iter()
+            .map(|recipe| {
+                recipe
+                    .iter()
+                    .map(|&(monster, family)| Parent {
+                        monster: monster.map(MonsterId),
+                        family: family.map(FamilyId),
+                    })
+                    .collect()
+            })
+            .collect(),
+        habitats: record
+            .habitats
+            .iter()
+            .map(|&(area, seasons)| {
+                let habitat = Habitat {
+                    seasons: seasons
+                        .iter()
+                        .map(|&(season, flags)| {
+                            (SeasonId(season), SeasonHabitat::new(flags).unwrap())
+                        })
+                        .collect(),
+                };
+                (AreaId(area), habitat)
+            })
+            .collect(),
+        details: MonsterDetails {
+            stats: record.stats,
+            size: record.size,
+            traits: record.traits.iter().map(name_record).collect(),
+            skills: record.skills.iter().map(name_record).collect(),
+        },
+    };
+    (MonsterId(record.id), monster)
+}
+
+pub fn get_lang_data() -> &'static HashMap<String, LangText> {
+    static DATA: OnceLock<HashMap<String, LangText>> = OnceLock::new();
+    DATA.get_or_init(|| {
+        LANG_TABLE
+            .iter()
+            .map(|&(key, ja, en)| {
+                let text = LangText {
+                    ja: ja.to_string(),
+                    en: en.to_string(),
+                };
+                (key.to_string(), text)
+            })
+            .collect()
+    })
+}
+
+pub fn get_base_meta() -> &'static DatasetMeta {
+    static DATA: OnceLock<DatasetMeta> = OnceLock::new();
+    DATA.get_or_init(|| {
+        let (ja, en, game_version, source_date) = BASE_META;
+        DatasetMeta {
+            name: LocalizedName {
+                ja: ja.to_string(),
+                en: en.map(|en| en.to_string()),
+            },
+            game_version: game_version.map(|v| v.to_string()),
+            source_date: source_date.map(|d| d.to_string()),
+        }
+    })
+}
+
+impl GameData {
+    /// Decoded from the static tables on the first call, with its references checked
+    /// like those of a dataset loaded at runtime.
+    pub fn embedded() -> Result<&'static Self, DataError> {
+        static DATA: OnceLock<Result<GameData, DataError>> = OnceLock::new();
+        let data = DATA.get_or_init(|| {
+            let data = GameData {
+                meta: get_base_meta().clone(),
+                monsters: get_monster_data().clone(),
+                families: get_family_data().clone(),
+                family_meta: get_family_meta().clone(),
+                ranks: get_rank_data().clone(),
+                areas: get_area_data().clone(),
+                seasons: get_season_data().clone(),
+                lang: get_lang_data().clone(),
+            };
+            data.check()?;
+            Ok(data)
+        });
+        data.as_ref().map_err(Clone::clone)
+    }
+}
+
+#[cfg(test)]
+const MONSTER_JSON_STR: &str = include_str!("data/monster.json");
+
+#[test]
+fn test_embedded_data() {
+    // The generated tables must hold the same data as the JSON parsers read
+    assert_eq!(
+        parse_id_name_json("area.json", include_str!("data/area.json")),
+        Ok(get_area_data().clone())
+    );
+    assert_eq!(
+        parse_id_name_json("family.json", include_str!("data/family.json")),
+        Ok(get_family_data().clone())
+    );
+    assert_eq!(
+        parse_id_name_json("rank.json", include_str!("data/rank.json")),
+        Ok(get_rank_data().clone())
+    );
+    assert_eq!(
+        parse_id_name_json("season.json", include_str!("data/season.json")),
+        Ok(get_season_data().clone())
+    );
+    assert_eq!(
+        parse_monster_json("monster.json", MONSTER_JSON_STR),
+        Ok(get_monster_data().clone())
+    );
+    assert_eq!(
+        parse_lang_json("lang.json", include_str!("data/lang.json")),
+        Ok(get_lang_data().clone())
+    );
+}
+
+#[test]
+fn test_family_meta() {
+    let data = GameData::embedded().unwrap();
+    assert_eq!(
+        data.family_meta
+            .keys()
+            .collect::<std::collections::HashSet<_>>(),
+        data.families.keys().collect()
+    );
+    for (&id, meta) in data.family_meta.iter() {
+        let class = data.family_class(id);
+        assert_eq!(class, format!("color-{:}", meta.slug));
+    }
+    assert_eq!(data.family_class(FamilyId(99)), "color-unknown");
+    assert_eq!(data.family_meta[&FamilyId(0)].color, "#1E90FF");
+}
+
+#[test]
+fn test_parse_id_name_json() {
+    let actual = parse_id_name_json::<RankId>("rank.json", r#"{"0": "X", "1": "S"}"#);
+    assert_eq!(
+        actual,
+        Ok(HashMap::from([
+            (RankId(0), LocalizedName::from("X")),
+            (RankId(1), LocalizedName::from("S"))
+        ]))
+    );
+
+    let actual = parse_id_name_json::<FamilyId>(
+        "family.json",
+        r#"{"0": {"ja": "スライム", "en": "Slime"}, "1": {"ja": "ドラゴン"}}"#,
+    );
+    assert_eq!(
+        actual,
+        Ok(HashMap::from([
+            (
+                FamilyId(0),
+                LocalizedName {
+                    ja: "スライム".to_string(),
+                    en: Some("Slime".to_string()),
+                }
+            ),
+            (FamilyId(1), LocalizedName::from("ドラゴン"))
+        ]))
+    );
+
+    let actual = parse_id_name_json::<RankId>("rank.json", r#"{"0": "X""#);
+    assert!(matches!(actual, Err(DataError::Syntax { .. })));
+
+    let actual = parse_id_name_json::<RankId>("rank.json", r#"["X"]"#);
+    assert_eq!(
+        actual,
+        Err(DataError::NotAnObject {
+            file: "rank.json".to_string(),
+            key: None,
+        })
+    );
+
+    let actual = parse_id_name_json::<RankId>("rank.json", r#"{"a": "X"}"#);
+    assert_eq!(
+        actual,
+        Err(DataError::InvalidKey {
+            file: "rank.json".to_string(),
+            key: "a".to_string(),
+        })
+    );
+
+    let actual = parse_id_name_json::<RankId>("rank.json", r#"{"0": 1}"#);
+    assert_eq!(
+        actual,
+        Err(DataError::InvalidValue {
+            file: "rank.json".to_string(),
+            key: "0".to_string(),
+            field: None,
+            message: "expected a string or an object with `ja` and optional `en`".to_string(),
+        })
+    );
+}
+
+#[test]
+fn test_parse_monster_json() {
+    let actual = parse_monster_json(
+        "monster.json",
+        r#"{"3": {"name": "a", "rank": 1, "family": 2, "parents": [[{"family": 0}, {"monster": 4}]], "habitats": {}}}"#,
+    );
+    assert_eq!(
+        actual,
+        Ok(HashMap::from([(
+            MonsterId(3),
+            Monster {
+                name: LocalizedName::from("a"),
+                rank: RankId(1),
+                family: FamilyId(2),
+                parents: vec![vec![
+                    Parent {
+                        monster: None,
+                        family: Some(FamilyId(0)),
+                    },
+                    Parent {
+                        monster: Some(MonsterId(4)),
+                        family: None,
+                    },
+                ]],
+                habitats: HashMap::new(),
+                details: MonsterDetails::default(),
+            },
+        )]))
+    );
+
+    let actual = parse_monster_json(
+        "monster.json",
+        r#"{"3": {"name": {"ja": "a", "en": "b"}, "rank": 1, "family": 2, "parents": [], "habitats": {}}}"#,
+    );
+    assert_eq!(
+        actual.map(|lut| lut[&MonsterId(3)].name.clone()),
+        Ok(LocalizedName {
+            ja: "a".to_string(),
+            en: Some("b".to_string()),
+        })
+    );
+
+    let actual = parse_monster_json(
+        "monster.json",
+        r#"{"3": {"name": "a", "family": 2, "parents": [], "habitats": {}}}"#,
+    );
+    assert_eq!(
+        actual,
+        Err(DataError::MissingField {
+            file: "monster.json".to_string(),
+            key: "3".to_string(),
+            field: "rank".to_string(),
+        })
+    );
+
+    let actual = parse_monster_json(
+        "monster.json",
+        r#"{"3": {"name": "a", "rank": "S", "family": 2, "parents": [], "habitats": {}}}"#,
+    );
+    match actual {
+        Err(DataError::InvalidValue {
+            file, key, field, ..
+        }) => {
+            assert_eq!(file, "monster.json");
+            assert_eq!(key, "3");
+            assert_eq!(field, Some("rank".to_string()));
+        }
+        _ => panic!("unexpected result: {:?}", actual),
+    }
+}
+
+#[test]
+fn test_load_monster_data() {
+    assert_eq!(
+        load_monster_data(
+            "monster.json",
+            MONSTER_JSON_STR,
+            GameData::embedded().unwrap()
+        ),
+        Ok(get_monster_data().clone())
+    );
+
+    let tables = GameData::embedded().unwrap();
+    let actual = load_monster_data(
+        "custom.json",
+        r#"{"3": {"name": "a", "rank": 1, "family": 2, "parents": [[{"family": 0}, {"monster": 4}]], "habitats": {}}}"#,
+        tables,
+    );
+    assert_eq!(
+        actual,
+        Err(DataError::InvalidValue {
+            file: "custom.json".to_string(),
+            key: "3".to_string(),
+            field: Some("parents".to_string()),
+            message: "unknown monster id 4".to_string(),
+        })
+    );
+
+    let actual = load_monster_data(
+        "custom.json",
+        r#"{"3": {"name": "a", "rank": 1, "family": 2, "parents": [[{"family": 0}, {}]], "habitats": {}}}"#,
+        tables,
+    );
+    assert_eq!(
+        actual,
+        Err(DataError::InvalidValue {
+            file: "custom.json".to_string(),
+            key: "3".to_string(),
+            field: Some("parents".to_string()),
+            message: "parent has neither a monster nor a family".to_string(),
+        })
+    );
+
+    let actual = load_monster_data(
+        "custom.json",
+        r#"{"3": {"name": "a", "rank": 100, "family": 2, "parents": [], "habitats": {}}}"#,
+        tables,
+    );
+    assert_eq!(
+        actual,
+        Err(DataError::InvalidValue {
+            file: "custom.json".to_string(),
+            key: "3".to_string(),
+            field: Some("rank".to_string()),
+            message: "unknown rank id 100".to_string(),
+        })
+    );
+
+    let actual = load_monster_data(
+        "custom.json",
+        r#"{"3": {"name": "a", "rank": 1, "family": 2, "parents": [], "habitats": {"99": {"conditions": {"0": [true]}}}}}"#,
+        tables,
+    );
+    assert_eq!(
+        actual,
+        Err(DataError::InvalidValue {
+            file: "custom.json".to_string(),
+            key: "3".to_string(),
+            field: Some("habitats".to_string()),
+            message: "unknown area id 99".to_string(),
+        })
+    );
+
+    let actual = load_monster_data(
+        "custom.json",
+        r#"{"3": {"name": "a", "rank": 1, "family": 2, "parents": [], "habitats": {"0": {"conditions": {"9": [true]}}}}}"#,
+        tables,
+    );
+    assert_eq!(
+        actual,
+        Err(DataError::InvalidValue {
+            file: "custom.json".to_string(),
+            key: "3".to_string(),
+            field: Some("habitats".to_string()),
+            message: "unknown season id 9".to_string(),
+        })
+    );
+}
+
+#[test]
+fn test_localized_name() {
+    let name = LocalizedName {
+        ja: "スライム".to_string(),
+        en: Some("Slime".to_string()),
+    };
+    assert_eq!(name.get(Locale::Ja), "スライム");
+    assert_eq!(name.get(Locale::En), "Slime");
+    assert_eq!(LocalizedName::from("スラ").get(Locale::En), "スラ");
+
+    let table = HashMap::from([(FamilyId(0), name)]);
+    assert_eq!(lookup_name(&table, &FamilyId(0), Locale::En), Some("Slime"));
+    assert_eq!(lookup_name(&table, &FamilyId(1), Locale::En), None);
+
+    assert_eq!(Locale::from_code("en"), Some(Locale::En));
+    assert_eq!(Locale::from_code("fr"), None);
+}
+
+#[test]
+fn test_lookup_text() {
+    let lang = get_lang_data();
+    assert_eq!(
+        lookup_text(lang, "monster_tree", Locale::En),
+        "Monster fusion tree"
+    );
+    assert_eq!(lookup_text(lang, "no_such_key", Locale::Ja), "no_such_key");
+}
+
+#[test]
+fn test_parse_habitats() {
+    let actual = parse_monster_json(
+        "monster.json",
+        r#"{"3": {"name": "a", "rank": 1, "family": 2, "parents": [], "habitats": {"5": {"conditions": {"0": [true, false], "2": [false]}}}}}"#,
+    );
+    assert_eq!(
+        actual.map(|lut| lut[&MonsterId(3)].habitats[&AreaId(5)].clone()),
+        Ok(Habitat {
+            seasons: HashMap::from([
+                (SeasonId(0), SeasonHabitat::new(&[true, false]).unwrap()),
+                (SeasonId(2), SeasonHabitat::new(&[false]).unwrap()),
+            ]),
+        })
+    );
+
+    let actual = parse_monster_json(
+        "monster.json",
+        r#"{"3": {"name": "a", "rank": 1, "family": 2, "parents": [], "habitats": {"5": {"conditions": {"0": []}}}}}"#,
+    );
+    match actual {
+        Err(DataError::InvalidValue { field, message, .. }) => {
+            assert_eq!(field, Some("habitats".to_string()));
+            assert!(message.contains("expected 1 or 2 habitat flags, found 0"));
+        }
+        _ => panic!("unexpected result: {:?}", actual),
+    }
+}
+
+#[test]
+fn test_parse_monster_details() {
+    let actual = parse_monster_json(
+        "monster.json",
+        r#"{"3": {"name": "a", "rank": 1, "family": 2, "parents": [], "habitats": {},
+            "stats": {"hp": 120, "mp": 40, "attack": 90, "defense": 70, "agility": 50, "wisdom": 30},
+            "size": "large", "traits": [{"ja": "メガボディ", "en": "Mega Body"}], "skills": ["ギガスラッシュ"]}}"#,
+    );
+    assert_eq!(
+        actual.map(|lut| lut[&MonsterId(3)].details.clone()),
+        Ok(MonsterDetails {
+            stats: Some(BaseStats {
+                hp: 120,
+                mp: 40,
+                attack: 90,
+                defense: 70,
+                agility: 50,
+                wisdom: 30,
+            }),
+            size: Some(BodySize::Large),
+            traits: vec![LocalizedName {
+                ja: "メガボディ".to_string(),
+                en: Some("Mega Body".to_string()),
+            }],
+            skills: vec!["ギガスラッシュ".into()],
+        })
+    );
+
+    // Older data files have none of the optional fields
+    let actual = parse_monster_json(
+        "monster.json",
+        r#"{"3": {"name": "a", "rank": 1, "family": 2, "parents": [], "habitats": {}, "size": null}}"#,
+    );
+    assert_eq!(
+        actual.map(|lut| lut[&MonsterId(3)].details.clone()),
+        Ok(MonsterDetails::default())
+    );
+
+    let actual = parse_monster_json(
+        "monster.json",
+        r#"{"3": {"name": "a", "rank": 1, "family": 2, "parents": [], "habitats": {}, "size": "huge"}}"#,
+    );
+    match actual {
+        Err(DataError::InvalidValue { field, .. }) => assert_eq!(field, Some("size".to_string())),
+        _ => panic!("unexpected result: {:?}", actual),
+    }
+}
+
+#[test]
+fn test_load_layer() {
+    let actual = load_layer(
+        "update.json",
+        r#"{
+            "meta": {"name": {"ja": "更新", "en": "Update"}, "game_version": "1.1.0"},
+            "monsters": {"600": {"name": "a", "rank": 1, "family": 2, "parents": [], "habitats": {}}},
+            "recipes": {"0": [[{"monster": 600}, {"family": 0}]]}
+        }"#,
+    );
+    assert_eq!(
+        actual,
+        Ok(Layer {
+            file: "update.json".to_string(),
+            meta: DatasetMeta {
+                name: LocalizedName {
+                    ja: "更新".to_string(),
+                    en: Some("Update".to_string()),
+                },
+                game_version: Some("1.1.0".to_string()),
+                source_date: None,
+            },
+            monsters: HashMap::from([(
+                MonsterId(600),
+                Monster {
+                    name: "a".into(),
+                    rank: RankId(1),
+                    family: FamilyId(2),
+                    parents: vec![],
+                    habitats: HashMap::new(),
+                    details: MonsterDetails::default(),
+                },
+            )]),
+            recipes: HashMap::from([(
+                MonsterId(0),
+                vec![vec![
+                    Parent {
+                        monster: Some(MonsterId(600)),
+                        family: None,
+                    },
+                    Parent {
+                        monster: None,
+                        family: Some(FamilyId(0)),
+                    },
+                ]],
+            )]),
+        })
+    );
+
+    let actual = load_layer("update.json", r#"{"monsters": {}}"#);
+    assert_eq!(
+        actual,
+        Err(DataError::InvalidValue {
+            file: "update.json".to_string(),
+            key: "meta".to_string(),
+            field: None,
+            message: "missing layer metadata".to_string(),
+        })
+    );
+}
+
+#[test]
+fn test_apply_layers() {
+    let tables = GameData::embedded().unwrap();
+    let base = get_monster_data();
+    let update = load_layer(
+        "update.json",
+        r#"{
+            "meta": {"name": "更新"},
+            "monsters": {"600": {"name": "a", "rank": 1, "family": 2, "parents": [[{"monster": 1}, {"monster": 2}]], "habitats": {}}},
+            "recipes": {"1": [[{"monster": 600}, {"family": 0}]]}
+        }"#,
+    )
+    .unwrap();
+    let dlc = load_layer(
+        "dlc.json",
+        r#"{
+            "meta": {"name": "DLC"},
+            "recipes": {"2": [[{"monster": 600}, {"monster": 600}]]}
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(apply_layers(base, &[], tables), Ok(base.clone()));
+
+    let actual = apply_layers(base, &[&update, &dlc], tables).unwrap();
+    assert_eq!(actual.len(), base.len() + 1);
+    assert_eq!(actual[&MonsterId(600)].name, LocalizedName::from("a"));
+    assert_eq!(
+        actual[&MonsterId(2)].parents.len(),
+        base[&MonsterId(2)].parents.len() + 1
+    );
+    assert_eq!(
+        actual[&MonsterId(1)].parents.len(),
+        base[&MonsterId(1)].parents.len() + 1
+    );
+
+    // The DLC recipe needs the monster introduced by the update
+    assert_eq!(
+        apply_layers(base, &[&dlc], tables),
+        Err(DataError::InvalidValue {
+            file: "dlc.json".to_string(),
+            key: "2".to_string(),
+            field: Some("parents".to_string()),
+            message: "unknown monster id 600".to_string(),
+        })
+    );
+    assert_eq!(
+        apply_layers(base, &[&update, &update], tables),
+        Err(DataError::InvalidValue {
+            file: "update.json".to_string(),
+            key: "600".to_string(),
+            field: None,
+            message: "monster id already exists".to_string(),
+        })
+    );
+}
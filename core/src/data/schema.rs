@@ -0,0 +1,759 @@
+//! `build.rs` includes this module too, so the embedded files and the datasets loaded at runtime
+//! go through the same validation.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize, Serializer};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::hash::Hash;
+
+macro_rules! define_id {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+        #[serde(transparent)]
+        pub struct $name(pub usize);
+
+        impl From<usize> for $name {
+            fn from(id: usize) -> Self {
+                Self(id)
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{:}", self.0)
+            }
+        }
+    };
+}
+
+define_id!(MonsterId);
+define_id!(FamilyId);
+define_id!(
+    /// A smaller id is a higher rank.
+    RankId
+);
+define_id!(AreaId);
+define_id!(SeasonId);
+
+/// Write a map in the order of its keys, so that the data files are stable.
+fn serialize_sorted<S: Serializer, K: Ord + Serialize, V: Serialize>(
+    map: &HashMap<K, V>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    map.iter()
+        .collect::<BTreeMap<&K, &V>>()
+        .serialize(serializer)
+}
+
+/// `monster.json` stores it as one or two flags, e.g. `[true, false]` or `[false]`.
+/// The source of the data does not document what they mean, so they are kept as they are.
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(try_from = "Vec<bool>", into = "Vec<bool>")]
+pub struct SeasonHabitat {
+    flags: [bool; 2],
+    len: usize,
+}
+
+impl SeasonHabitat {
+    pub fn new(flags: &[bool]) -> Result<Self, String> {
+        match *flags {
+            [a] => Ok(Self {
+                flags: [a, false],
+                len: 1,
+            }),
+            [a, b] => Ok(Self {
+                flags: [a, b],
+                len: 2,
+            }),
+            _ => Err(format!(
+                "expected 1 or 2 habitat flags, found {:}",
+                flags.len()
+            )),
+        }
+    }
+
+    pub fn flags(&self) -> &[bool] {
+        &self.flags[..self.len]
+    }
+}
+
+impl TryFrom<Vec<bool>> for SeasonHabitat {
+    type Error = String;
+
+    fn try_from(flags: Vec<bool>) -> Result<Self, Self::Error> {
+        Self::new(&flags)
+    }
+}
+
+impl From<SeasonHabitat> for Vec<bool> {
+    fn from(habitat: SeasonHabitat) -> Self {
+        habitat.flags().to_vec()
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct Habitat {
+    #[serde(rename = "conditions", serialize_with = "serialize_sorted")]
+    pub seasons: HashMap<SeasonId, SeasonHabitat>,
+}
+
+impl Habitat {
+    pub fn in_season(&self, season: SeasonId) -> Option<&SeasonHabitat> {
+        self.seasons.get(&season)
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct Parent {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub monster: Option<MonsterId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub family: Option<FamilyId>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub enum Locale {
+    #[default]
+    Ja,
+    En,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::Ja, Locale::En];
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Ja => "ja",
+            Self::En => "en",
+        }
+    }
+
+    pub fn native_name(&self) -> &'static str {
+        match self {
+            Self::Ja => "日本語",
+            Self::En => "English",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|l| l.code() == code)
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(untagged)]
+enum LocalizedNameRepr {
+    Plain(String),
+    Localized { ja: String, en: Option<String> },
+}
+
+/// In the data files it is either a plain Japanese string or an object with `ja` and optional `en`.
+/// The embedded monster and area names are not translated yet and show in Japanese in both locales.
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Default, Debug)]
+#[serde(from = "LocalizedNameRepr", into = "LocalizedNameRepr")]
+pub struct LocalizedName {
+    pub ja: String,
+    pub en: Option<String>,
+}
+
+impl LocalizedName {
+    /// Name in `locale`, falling back to Japanese when it is not translated.
+    pub fn get(&self, locale: Locale) -> &str {
+        match locale {
+            Locale::Ja => &self.ja,
+            Locale::En => self.en.as_deref().unwrap_or(&self.ja),
+        }
+    }
+}
+
+impl From<LocalizedNameRepr> for LocalizedName {
+    fn from(repr: LocalizedNameRepr) -> Self {
+        match repr {
+            LocalizedNameRepr::Plain(ja) => Self { ja, en: None },
+            LocalizedNameRepr::Localized { ja, en } => Self { ja, en },
+        }
+    }
+}
+
+impl From<LocalizedName> for LocalizedNameRepr {
+    fn from(name: LocalizedName) -> Self {
+        match name.en {
+            None => Self::Plain(name.ja),
+            Some(en) => Self::Localized {
+                ja: name.ja,
+                en: Some(en),
+            },
+        }
+    }
+}
+
+impl From<&str> for LocalizedName {
+    fn from(ja: &str) -> Self {
+        Self {
+            ja: ja.to_string(),
+            en: None,
+        }
+    }
+}
+
+pub fn lookup_name<'a, K: Hash + Eq>(
+    table: &'a HashMap<K, LocalizedName>,
+    id: &K,
+    locale: Locale,
+) -> Option<&'a str> {
+    table.get(id).map(|name| name.get(locale))
+}
+
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct Monster {
+    pub name: LocalizedName,
+    pub rank: RankId,
+    pub family: FamilyId,
+    pub parents: Vec<Vec<Parent>>,
+    #[serde(serialize_with = "serialize_sorted")]
+    pub habitats: HashMap<AreaId, Habitat>,
+    #[serde(flatten)]
+    pub details: MonsterDetails,
+}
+
+/// Stats of a monster at level 1.
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BaseStats {
+    pub hp: u32,
+    pub mp: u32,
+    pub attack: u32,
+    pub defense: u32,
+    pub agility: u32,
+    pub wisdom: u32,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum BodySize {
+    Normal,
+    Large,
+    Giant,
+}
+
+/// Older data files lack these fields, so every field defaults to empty.
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Default, Debug)]
+pub struct MonsterDetails {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stats: Option<BaseStats>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<BodySize>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub traits: Vec<LocalizedName>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skills: Vec<LocalizedName>,
+}
+
+#[derive(Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct LangText {
+    pub ja: String,
+    pub en: String,
+}
+
+impl LangText {
+    pub fn get(&self, locale: Locale) -> &str {
+        match locale {
+            Locale::Ja => &self.ja,
+            Locale::En => &self.en,
+        }
+    }
+}
+
+/// A key missing from `lang.json` is shown as is instead of failing the whole view.
+pub fn lookup_text<'a>(
+    lang: &'a HashMap<String, LangText>,
+    key: &'a str,
+    locale: Locale,
+) -> &'a str {
+    lang.get(key).map(|text| text.get(locale)).unwrap_or(key)
+}
+
+/// Every variant names the file, and the entry key and field when they are known.
+#[derive(Clone, PartialEq, Debug)]
+pub enum DataError {
+    Syntax {
+        file: String,
+        message: String,
+    },
+    NotAnObject {
+        file: String,
+        key: Option<String>,
+    },
+    InvalidKey {
+        file: String,
+        key: String,
+    },
+    MissingField {
+        file: String,
+        key: String,
+        field: String,
+    },
+    InvalidValue {
+        file: String,
+        key: String,
+        field: Option<String>,
+        message: String,
+    },
+}
+
+impl fmt::Display for DataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Syntax { file, message } => write!(f, "{:}: {:}", file, message),
+            Self::NotAnObject { file, key: None } => {
+                write!(f, "{:}: top level is not an object", file)
+            }
+            Self::NotAnObject {
+                file,
+                key: Some(key),
+            } => write!(f, "{:}: entry \"{:}\" is not an object", file, key),
+            Self::InvalidKey { file, key } => {
+                write!(f, "{:}: key \"{:}\" is not a numeric id", file, key)
+            }
+            Self::MissingField { file, key, field } => {
+                write!(f, "{:}: entry \"{:}\" has no field `{:}`", file, key, field)
+            }
+            Self::InvalidValue {
+                file,
+                key,
+                field: None,
+                message,
+            } => write!(f, "{:}: entry \"{:}\": {:}", file, key, message),
+            Self::InvalidValue {
+                file,
+                key,
+                field: Some(field),
+                message,
+            } => write!(
+                f,
+                "{:}: entry \"{:}\", field `{:}`: {:}",
+                file, key, field, message
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DataError {}
+
+fn parse_json_object(file: &str, json_str: &str) -> Result<Map<String, Value>, DataError> {
+    let json: Value = serde_json::from_str(json_str).map_err(|e| DataError::Syntax {
+        file: file.to_string(),
+        message: e.to_string(),
+    })?;
+    match json {
+        Value::Object(obj) => Ok(obj),
+        _ => Err(DataError::NotAnObject {
+            file: file.to_string(),
+            key: None,
+        }),
+    }
+}
+
+fn parse_key<K: From<usize>>(file: &str, key: &str) -> Result<K, DataError> {
+    key.parse::<usize>()
+        .map(K::from)
+        .map_err(|_| DataError::InvalidKey {
+            file: file.to_string(),
+            key: key.to_string(),
+        })
+}
+
+fn parse_entry_object<'a>(
+    file: &str,
+    key: &str,
+    value: &'a Value,
+) -> Result<&'a Map<String, Value>, DataError> {
+    value.as_object().ok_or_else(|| DataError::NotAnObject {
+        file: file.to_string(),
+        key: Some(key.to_string()),
+    })
+}
+
+fn parse_field<T: DeserializeOwned>(
+    file: &str,
+    key: &str,
+    obj: &Map<String, Value>,
+    field: &str,
+) -> Result<T, DataError> {
+    let value = obj.get(field).ok_or_else(|| DataError::MissingField {
+        file: file.to_string(),
+        key: key.to_string(),
+        field: field.to_string(),
+    })?;
+    T::deserialize(value).map_err(|e| DataError::InvalidValue {
+        file: file.to_string(),
+        key: key.to_string(),
+        field: Some(field.to_string()),
+        message: e.to_string(),
+    })
+}
+
+/// Like `parse_field`, but a missing or `null` field gives the default value.
+fn parse_optional_field<T: DeserializeOwned + Default>(
+    file: &str,
+    key: &str,
+    obj: &Map<String, Value>,
+    field: &str,
+) -> Result<T, DataError> {
+    match obj.get(field) {
+        None | Some(Value::Null) => Ok(T::default()),
+        Some(_) => parse_field(file, key, obj, field),
+    }
+}
+
+pub fn parse_id_name_json<K: From<usize> + Hash + Eq>(
+    file: &str,
+    json_str: &str,
+) -> Result<HashMap<K, LocalizedName>, DataError> {
+    let obj = parse_json_object(file, json_str)?;
+    let mut data: HashMap<K, LocalizedName> = HashMap::new();
+
+    for (key, value) in obj.iter() {
+        let num_key = parse_key(file, key)?;
+        let name = LocalizedName::deserialize(value).map_err(|_| DataError::InvalidValue {
+            file: file.to_string(),
+            key: key.to_string(),
+            field: None,
+            message: "expected a string or an object with `ja` and optional `en`".to_string(),
+        })?;
+        data.insert(num_key, name);
+    }
+
+    Ok(data)
+}
+
+pub fn parse_monster_json(
+    file: &str,
+    json_str: &str,
+) -> Result<HashMap<MonsterId, Monster>, DataError> {
+    parse_monster_map(file, &parse_json_object(file, json_str)?)
+}
+
+fn parse_monster_map(
+    file: &str,
+    obj: &Map<String, Value>,
+) -> Result<HashMap<MonsterId, Monster>, DataError> {
+    let mut data: HashMap<MonsterId, Monster> = HashMap::new();
+
+    for (key, value) in obj.iter() {
+        let num_key = parse_key(file, key)?;
+        let entry = parse_entry_object(file, key, value)?;
+        let monster = Monster {
+            name: parse_field(file, key, entry, "name")?,
+            rank: parse_field(file, key, entry, "rank")?,
+            family: parse_field(file, key, entry, "family")?,
+            parents: parse_field(file, key, entry, "parents")?,
+            habitats: parse_field(file, key, entry, "habitats")?,
+            details: MonsterDetails {
+                stats: parse_optional_field(file, key, entry, "stats")?,
+                size: parse_optional_field(file, key, entry, "size")?,
+                traits: parse_optional_field(file, key, entry, "traits")?,
+                skills: parse_optional_field(file, key, entry, "skills")?,
+            },
+        };
+        data.insert(num_key, monster);
+    }
+
+    Ok(data)
+}
+
+pub fn parse_lang_json(file: &str, json_str: &str) -> Result<HashMap<String, LangText>, DataError> {
+    let obj = parse_json_object(file, json_str)?;
+    obj.iter()
+        .map(|(key, value)| {
+            let entry = parse_entry_object(file, key, value)?;
+            let text = LangText {
+                ja: parse_field(file, key, entry, "ja")?,
+                en: parse_field(file, key, entry, "en")?,
+            };
+            Ok((key.clone(), text))
+        })
+        .collect()
+}
+
+pub fn parse_family_meta_json(
+    file: &str,
+    json_str: &str,
+) -> Result<HashMap<FamilyId, FamilyMeta>, DataError> {
+    let obj = parse_json_object(file, json_str)?;
+    obj.iter()
+        .map(|(key, value)| {
+            let entry = parse_entry_object(file, key, value)?;
+            let meta = FamilyMeta {
+                slug: parse_field(file, key, entry, "slug")?,
+                color: parse_field(file, key, entry, "color")?,
+                icon: parse_field(file, key, entry, "icon")?,
+            };
+            let invalid = |field: &str, message: &str| DataError::InvalidValue {
+                file: file.to_string(),
+                key: key.to_string(),
+                field: Some(field.to_string()),
+                message: message.to_string(),
+            };
+
+            let is_slug = !meta.slug.is_empty()
+                && meta
+                    .slug
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c == '-');
+            if !is_slug {
+                return Err(invalid("slug", "expected lowercase letters and hyphens"));
+            }
+            let is_hex_color = meta.color.len() == 7
+                && meta.color.starts_with('#')
+                && meta.color[1..].chars().all(|c| c.is_ascii_hexdigit());
+            if !is_hex_color {
+                return Err(invalid("color", "expected #rrggbb"));
+            }
+            if meta.icon.is_empty() {
+                return Err(invalid("icon", "expected a non-empty string"));
+            }
+            Ok((parse_key(file, key)?, meta))
+        })
+        .collect()
+}
+
+pub fn parse_meta_json(file: &str, json_str: &str) -> Result<DatasetMeta, DataError> {
+    let obj = parse_json_object(file, json_str)?;
+    Ok(DatasetMeta {
+        name: parse_field(file, "meta", &obj, "name")?,
+        game_version: parse_optional_field(file, "meta", &obj, "game_version")?,
+        source_date: parse_optional_field(file, "meta", &obj, "source_date")?,
+    })
+}
+
+#[derive(Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct FamilyMeta {
+    /// Names the `color-<slug>` class of `public/main.css`.
+    pub slug: String,
+    /// `#rrggbb`, for renderers without the stylesheet.
+    pub color: String,
+    pub icon: String,
+}
+
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
+pub struct GameData {
+    pub meta: DatasetMeta,
+    pub monsters: HashMap<MonsterId, Monster>,
+    pub families: HashMap<FamilyId, LocalizedName>,
+    pub family_meta: HashMap<FamilyId, FamilyMeta>,
+    pub ranks: HashMap<RankId, LocalizedName>,
+    pub areas: HashMap<AreaId, LocalizedName>,
+    pub seasons: HashMap<SeasonId, LocalizedName>,
+    pub lang: HashMap<String, LangText>,
+}
+
+impl GameData {
+    pub fn text<'a>(&'a self, key: &'a str, locale: Locale) -> &'a str {
+        lookup_text(&self.lang, key, locale)
+    }
+
+    pub fn check(&self) -> Result<(), DataError> {
+        let ids = self.monsters.keys().copied().collect::<Vec<_>>();
+        check_references("monster.json", &self.monsters, &ids, self)
+    }
+
+    pub fn family_class(&self, family: FamilyId) -> String {
+        match self.family_meta.get(&family) {
+            Some(meta) => format!("color-{:}", meta.slug),
+            None => "color-unknown".to_string(),
+        }
+    }
+}
+
+/// Check that the rank, family, parent and habitat ids of the monsters in `ids` exist
+/// in `monsters` and the name tables of `tables`,
+/// since the recipe engine and the views look them up without fallback.
+fn check_references(
+    file: &str,
+    monsters: &HashMap<MonsterId, Monster>,
+    ids: &[MonsterId],
+    tables: &GameData,
+) -> Result<(), DataError> {
+    let mut ids = ids.to_vec();
+    ids.sort();
+    for id in ids {
+        let monster = &monsters[&id];
+        let unknown_id =
+            |field: &str, kind: &str, value: &dyn fmt::Display| DataError::InvalidValue {
+                file: file.to_string(),
+                key: id.to_string(),
+                field: Some(field.to_string()),
+                message: format!("unknown {:} id {:}", kind, value),
+            };
+
+        if !tables.ranks.contains_key(&monster.rank) {
+            return Err(unknown_id("rank", "rank", &monster.rank));
+        }
+        if !tables.families.contains_key(&monster.family) {
+            return Err(unknown_id("family", "family", &monster.family));
+        }
+        for parent in monster.parents.iter().flatten() {
+            match (parent.monster, parent.family) {
+                (Some(m), _) if !monsters.contains_key(&m) => {
+                    return Err(unknown_id("parents", "monster", &m));
+                }
+                (_, Some(f)) if !tables.families.contains_key(&f) => {
+                    return Err(unknown_id("parents", "family", &f));
+                }
+                (None, None) => {
+                    return Err(DataError::InvalidValue {
+                        file: file.to_string(),
+                        key: id.to_string(),
+                        field: Some("parents".to_string()),
+                        message: "parent has neither a monster nor a family".to_string(),
+                    });
+                }
+                _ => (),
+            }
+        }
+        let mut areas = monster.habitats.iter().collect::<Vec<_>>();
+        areas.sort_by_key(|(area, _)| **area);
+        for (area, habitat) in areas {
+            if !tables.areas.contains_key(area) {
+                return Err(unknown_id("habitats", "area", area));
+            }
+            let mut seasons = habitat.seasons.keys().collect::<Vec<_>>();
+            seasons.sort();
+            if let Some(season) = seasons
+                .into_iter()
+                .find(|s| !tables.seasons.contains_key(s))
+            {
+                return Err(unknown_id("habitats", "season", season));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Besides the schema, the ids the file refers to are checked against the name tables of `tables`.
+pub fn load_monster_data(
+    file: &str,
+    json_str: &str,
+    tables: &GameData,
+) -> Result<HashMap<MonsterId, Monster>, DataError> {
+    let monsters = parse_monster_json(file, json_str)?;
+    check_references(
+        file,
+        &monsters,
+        &monsters.keys().copied().collect::<Vec<_>>(),
+        tables,
+    )?;
+    Ok(monsters)
+}
+
+#[derive(Deserialize, Clone, PartialEq, Eq, Default, Debug)]
+pub struct DatasetMeta {
+    pub name: LocalizedName,
+    #[serde(default)]
+    pub game_version: Option<String>,
+    /// Date the data was taken from its source, e.g. `2024-01-10`.
+    #[serde(default)]
+    pub source_date: Option<String>,
+}
+
+/// A patch stacked on the base dataset, such as a game update or a DLC.
+///
+/// The file is an object with `meta` (see [`DatasetMeta`]), `monsters` in the `monster.json`
+/// format for the monsters it introduces, and `recipes` mapping existing monster ids to
+/// the recipes it adds. `monsters` and `recipes` may be omitted.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Layer {
+    pub file: String,
+    pub meta: DatasetMeta,
+    pub monsters: HashMap<MonsterId, Monster>,
+    pub recipes: HashMap<MonsterId, Vec<Vec<Parent>>>,
+}
+
+pub fn load_layer(file: &str, json_str: &str) -> Result<Layer, DataError> {
+    let obj = parse_json_object(file, json_str)?;
+    let invalid_meta = |message: String| DataError::InvalidValue {
+        file: file.to_string(),
+        key: "meta".to_string(),
+        field: None,
+        message,
+    };
+    let meta = match obj.get("meta") {
+        Some(value) => DatasetMeta::deserialize(value).map_err(|e| invalid_meta(e.to_string()))?,
+        None => return Err(invalid_meta("missing layer metadata".to_string())),
+    };
+    let monsters = match obj.get("monsters") {
+        Some(value) => parse_monster_map(file, parse_entry_object(file, "monsters", value)?)?,
+        None => HashMap::new(),
+    };
+    let recipes = match obj.get("recipes") {
+        Some(value) => {
+            let entries = parse_entry_object(file, "recipes", value)?;
+            entries
+                .iter()
+                .map(|(key, parents)| {
+                    let id = parse_key(file, key)?;
+                    let parents = Vec::<Vec<Parent>>::deserialize(parents).map_err(|e| {
+                        DataError::InvalidValue {
+                            file: file.to_string(),
+                            key: key.to_string(),
+                            field: None,
+                            message: e.to_string(),
+                        }
+                    })?;
+                    Ok((id, parents))
+                })
+                .collect::<Result<_, DataError>>()?
+        }
+        None => HashMap::new(),
+    };
+
+    Ok(Layer {
+        file: file.to_string(),
+        meta,
+        monsters,
+        recipes,
+    })
+}
+
+/// Stack `layers` on `base` in order.
+/// A layer may only refer to monsters of the base and of the layers before it,
+/// and to the name tables of `tables`.
+pub fn apply_layers(
+    base: &HashMap<MonsterId, Monster>,
+    layers: &[&Layer],
+    tables: &GameData,
+) -> Result<HashMap<MonsterId, Monster>, DataError> {
+    let mut monsters = base.clone();
+
+    for layer in layers.iter() {
+        let invalid = |id: &MonsterId, message: &str| DataError::InvalidValue {
+            file: layer.file.clone(),
+            key: id.to_string(),
+            field: None,
+            message: message.to_string(),
+        };
+
+        let mut changed: Vec<MonsterId> = vec![];
+        for (id, monster) in layer.monsters.iter() {
+            if monsters.contains_key(id) {
+                return Err(invalid(id, "monster id already exists"));
+            }
+            monsters.insert(*id, monster.clone());
+            changed.push(*id);
+        }
+        for (id, recipes) in layer.recipes.iter() {
+            match monsters.get_mut(id) {
+                Some(monster) => monster.parents.extend(recipes.iter().cloned()),
+                None => return Err(invalid(id, "recipes for an unknown monster")),
+            }
+            changed.push(*id);
+        }
+        check_references(&layer.file, &monsters, &changed, tables)?;
+    }
+
+    Ok(monsters)
+}
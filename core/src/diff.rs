@@ -0,0 +1,433 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write;
+
+use super::data::{
+    lookup_name, AreaId, FamilyId, GameData, Locale, LocalizedName, Monster, MonsterDetails,
+    MonsterId, Parent, RankId, SeasonHabitat, SeasonId,
+};
+use super::recipe::MonsterTreeBuilder;
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum MonsterChange {
+    Name {
+        old: LocalizedName,
+        new: LocalizedName,
+    },
+    Rank {
+        old: RankId,
+        new: RankId,
+    },
+    Family {
+        old: FamilyId,
+        new: FamilyId,
+    },
+    RecipeAdded(Vec<Parent>),
+    RecipeRemoved(Vec<Parent>),
+    /// The monster can be scouted in an area and season where it could not before,
+    /// or no longer can when `old` is set and `new` is not, or its habitat flags changed.
+    Habitat {
+        area: AreaId,
+        season: SeasonId,
+        old: Option<SeasonHabitat>,
+        new: Option<SeasonHabitat>,
+    },
+    /// The stats, size, traits or skills changed.
+    Details {
+        old: MonsterDetails,
+        new: MonsterDetails,
+    },
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct DatasetDiff {
+    pub added: Vec<MonsterId>,
+    pub removed: Vec<MonsterId>,
+    pub changed: Vec<(MonsterId, Vec<MonsterChange>)>,
+    /// Monsters in both versions whose recipe tree is built differently,
+    /// because their own recipes or those of a monster in their tree changed.
+    pub affected_trees: Vec<MonsterId>,
+}
+
+impl DatasetDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+fn habitat_entries(monster: &Monster) -> HashMap<(AreaId, SeasonId), SeasonHabitat> {
+    monster
+        .habitats
+        .iter()
+        .flat_map(|(&area, habitat)| {
+            habitat
+                .seasons
+                .iter()
+                .map(move |(&season, &h)| ((area, season), h))
+        })
+        .collect()
+}
+
+fn diff_monster(old: &Monster, new: &Monster) -> Vec<MonsterChange> {
+    let mut changes = vec![];
+    if old.name != new.name {
+        changes.push(MonsterChange::Name {
+            old: old.name.clone(),
+            new: new.name.clone(),
+        });
+    }
+    if old.rank != new.rank {
+        changes.push(MonsterChange::Rank {
+            old: old.rank,
+            new: new.rank,
+        });
+    }
+    if old.family != new.family {
+        changes.push(MonsterChange::Family {
+            old: old.family,
+            new: new.family,
+        });
+    }
+
+    // The order of the recipes does not matter
+    for recipe in old.parents.iter() {
+        if !new.parents.contains(recipe) {
+            changes.push(MonsterChange::RecipeRemoved(recipe.clone()));
+        }
+    }
+    for recipe in new.parents.iter() {
+        if !old.parents.contains(recipe) {
+            changes.push(MonsterChange::RecipeAdded(recipe.clone()));
+        }
+    }
+
+    let old_habitats = habitat_entries(old);
+    let new_habitats = habitat_entries(new);
+    let keys = old_habitats
+        .keys()
+        .chain(new_habitats.keys())
+        .copied()
+        .collect::<BTreeSet<(AreaId, SeasonId)>>();
+    for (area, season) in keys {
+        let old = old_habitats.get(&(area, season)).copied();
+        let new = new_habitats.get(&(area, season)).copied();
+        if old != new {
+            changes.push(MonsterChange::Habitat {
+                area,
+                season,
+                old,
+                new,
+            });
+        }
+    }
+
+    if old.details != new.details {
+        changes.push(MonsterChange::Details {
+            old: old.details.clone(),
+            new: new.details.clone(),
+        });
+    }
+    changes
+}
+
+/// Both maps must be complete, i.e. every parent they refer to exists in the same map.
+pub fn diff_monsters(
+    old: &HashMap<MonsterId, Monster>,
+    new: &HashMap<MonsterId, Monster>,
+) -> DatasetDiff {
+    let mut diff = DatasetDiff::default();
+    let ids = old
+        .keys()
+        .chain(new.keys())
+        .copied()
+        .collect::<BTreeSet<MonsterId>>();
+    let old_builder = MonsterTreeBuilder::new(old.clone());
+    let new_builder = MonsterTreeBuilder::new(new.clone());
+
+    for id in ids {
+        match (old.get(&id), new.get(&id)) {
+            (None, Some(_)) => diff.added.push(id),
+            (Some(_), None) => diff.removed.push(id),
+            (Some(old_monster), Some(new_monster)) => {
+                let changes = diff_monster(old_monster, new_monster);
+                if !changes.is_empty() {
+                    diff.changed.push((id, changes));
+                }
+                if old_builder.build(id) != new_builder.build(id) {
+                    diff.affected_trees.push(id);
+                }
+            }
+            (None, None) => unreachable!(),
+        }
+    }
+    diff
+}
+
+/// Format the diff for review. Names are taken from the new version,
+/// or from the old one for removed monsters.
+pub fn to_report(
+    diff: &DatasetDiff,
+    old: &HashMap<MonsterId, Monster>,
+    new: &HashMap<MonsterId, Monster>,
+    data: &GameData,
+    locale: Locale,
+) -> String {
+    let monster_name = |id: &MonsterId| {
+        new.get(id)
+            .or_else(|| old.get(id))
+            .map(|m| m.name.get(locale).to_string())
+            .unwrap_or_else(|| id.to_string())
+    };
+    let table_name = |name: Option<&str>, id: usize| {
+        name.map(|n| n.to_string())
+            .unwrap_or_else(|| id.to_string())
+    };
+    let rank_name = |id: &RankId| table_name(lookup_name(&data.ranks, id, locale), id.0);
+    let family_name = |id: &FamilyId| table_name(lookup_name(&data.families, id, locale), id.0);
+    let recipe = |parents: &Vec<Parent>| {
+        parents
+            .iter()
+            .map(|p| match (p.monster, p.family) {
+                (Some(m), _) => monster_name(&m),
+                (None, Some(f)) => format!("[{:}]", family_name(&f)),
+                (None, None) => "?".to_string(),
+            })
+            .collect::<Vec<String>>()
+            .join(" + ")
+    };
+    let habitat = |h: &Option<SeasonHabitat>| match h {
+        None => "-".to_string(),
+        Some(h) => format!("{:?}", h.flags()),
+    };
+    let names = |names: &Vec<LocalizedName>| match names.is_empty() {
+        true => "-".to_string(),
+        false => names
+            .iter()
+            .map(|n| n.get(locale))
+            .collect::<Vec<&str>>()
+            .join(", "),
+    };
+    let details = |old: &MonsterDetails, new: &MonsterDetails| {
+        let mut lines = vec![];
+        if old.stats != new.stats {
+            let stats = |d: &MonsterDetails| match d.stats {
+                None => "-".to_string(),
+                Some(s) => format!(
+                    "{:}/{:}/{:}/{:}/{:}/{:}",
+                    s.hp, s.mp, s.attack, s.defense, s.agility, s.wisdom
+                ),
+            };
+            lines.push(format!(
+                "stats (HP/MP/ATK/DEF/AGI/WIS): {:} -> {:}",
+                stats(old),
+                stats(new)
+            ));
+        }
+        if old.size != new.size {
+            let size = |d: &MonsterDetails| match d.size {
+                None => "-".to_string(),
+                Some(s) => format!("{:?}", s),
+            };
+            lines.push(format!("size: {:} -> {:}", size(old), size(new)));
+        }
+        if old.traits != new.traits {
+            lines.push(format!(
+                "traits: {:} -> {:}",
+                names(&old.traits),
+                names(&new.traits)
+            ));
+        }
+        if old.skills != new.skills {
+            lines.push(format!(
+                "skills: {:} -> {:}",
+                names(&old.skills),
+                names(&new.skills)
+            ));
+        }
+        lines.join("\n    ")
+    };
+
+    let mut out = String::new();
+    for (title, ids) in [("Added", &diff.added), ("Removed", &diff.removed)] {
+        if ids.is_empty() {
+            continue;
+        }
+        writeln!(out, "{:}:", title).unwrap();
+        for id in ids.iter() {
+            writeln!(out, "  {:} {:}", id, monster_name(id)).unwrap();
+        }
+    }
+    if !diff.changed.is_empty() {
+        writeln!(out, "Changed:").unwrap();
+    }
+    for (id, changes) in diff.changed.iter() {
+        writeln!(out, "  {:} {:}", id, monster_name(id)).unwrap();
+        for change in changes.iter() {
+            let line = match change {
+                MonsterChange::Name { old, new } => {
+                    format!("name: {:} -> {:}", old.get(locale), new.get(locale))
+                }
+                MonsterChange::Rank { old, new } => {
+                    format!("rank: {:} -> {:}", rank_name(old), rank_name(new))
+                }
+                MonsterChange::Family { old, new } => {
+                    format!("family: {:} -> {:}", family_name(old), family_name(new))
+                }
+                MonsterChange::RecipeAdded(parents) => format!("+ recipe: {:}", recipe(parents)),
+                MonsterChange::RecipeRemoved(parents) => {
+                    format!("- recipe: {:}", recipe(parents))
+                }
+                MonsterChange::Habitat {
+                    area,
+                    season,
+                    old,
+                    new,
+                } => format!(
+                    "habitat {:} ({:}): {:} -> {:}",
+                    table_name(lookup_name(&data.areas, area, locale), area.0),
+                    table_name(lookup_name(&data.seasons, season, locale), season.0),
+                    habitat(old),
+                    habitat(new)
+                ),
+                MonsterChange::Details { old, new } => details(old, new),
+            };
+            writeln!(out, "    {:}", line).unwrap();
+        }
+    }
+    if !diff.affected_trees.is_empty() {
+        writeln!(out, "Recipe trees that change:").unwrap();
+        for id in diff.affected_trees.iter() {
+            writeln!(out, "  {:} {:}", id, monster_name(id)).unwrap();
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+fn test_monster(name: &str, parents: Vec<Vec<Parent>>) -> Monster {
+    Monster {
+        name: name.into(),
+        rank: RankId(1),
+        family: FamilyId(0),
+        parents,
+        habitats: HashMap::new(),
+        details: MonsterDetails::default(),
+    }
+}
+
+#[cfg(test)]
+fn monster_parent(id: usize) -> Parent {
+    Parent {
+        monster: Some(MonsterId(id)),
+        family: None,
+    }
+}
+
+#[test]
+fn test_diff_monsters() {
+    use super::data::Habitat;
+
+    let old = HashMap::from([
+        (MonsterId(1), test_monster("a", vec![])),
+        (MonsterId(2), test_monster("b", vec![])),
+        (
+            MonsterId(3),
+            test_monster("c", vec![vec![monster_parent(1), monster_parent(2)]]),
+        ),
+        (
+            MonsterId(4),
+            test_monster("d", vec![vec![monster_parent(3), monster_parent(3)]]),
+        ),
+        (MonsterId(5), test_monster("e", vec![])),
+    ]);
+    let mut new = old.clone();
+    new.remove(&MonsterId(5));
+    new.insert(MonsterId(6), test_monster("f", vec![]));
+    new.get_mut(&MonsterId(2)).unwrap().rank = RankId(2);
+    new.get_mut(&MonsterId(2)).unwrap().habitats = HashMap::from([(
+        AreaId(0),
+        Habitat {
+            seasons: HashMap::from([(SeasonId(1), SeasonHabitat::new(&[true, false]).unwrap())]),
+        },
+    )]);
+    new.get_mut(&MonsterId(3)).unwrap().parents = vec![
+        vec![monster_parent(6), monster_parent(1)],
+        vec![monster_parent(1), monster_parent(2)],
+    ];
+
+    let diff = diff_monsters(&old, &new);
+    assert_eq!(diff.added, vec![MonsterId(6)]);
+    assert_eq!(diff.removed, vec![MonsterId(5)]);
+    assert_eq!(
+        diff.changed,
+        vec![
+            (
+                MonsterId(2),
+                vec![
+                    MonsterChange::Rank {
+                        old: RankId(1),
+                        new: RankId(2),
+                    },
+                    MonsterChange::Habitat {
+                        area: AreaId(0),
+                        season: SeasonId(1),
+                        old: None,
+                        new: Some(SeasonHabitat::new(&[true, false]).unwrap()),
+                    },
+                ],
+            ),
+            (
+                MonsterId(3),
+                vec![MonsterChange::RecipeAdded(vec![
+                    monster_parent(6),
+                    monster_parent(1),
+                ])],
+            ),
+        ]
+    );
+    // The first recipe with the fewest standard parents is picked, so both trees above 3 change
+    assert_eq!(diff.affected_trees, vec![MonsterId(3), MonsterId(4)]);
+    assert!(!diff.is_empty());
+
+    let report = to_report(&diff, &old, &new, GameData::embedded().unwrap(), Locale::En);
+    assert!(report.contains("Added:\n  6 f\n"));
+    assert!(report.contains("Removed:\n  5 e\n"));
+    assert!(report.contains("    + recipe: f + a\n"));
+    assert!(report.contains("Recipe trees that change:\n  3 c\n  4 d\n"));
+}
+
+#[test]
+fn test_diff_details() {
+    use super::data::BodySize;
+
+    let old = HashMap::from([(MonsterId(1), test_monster("a", vec![]))]);
+    let mut new = old.clone();
+    new.get_mut(&MonsterId(1)).unwrap().details.size = Some(BodySize::Large);
+
+    let diff = diff_monsters(&old, &new);
+    assert_eq!(
+        diff.changed,
+        vec![(
+            MonsterId(1),
+            vec![MonsterChange::Details {
+                old: MonsterDetails::default(),
+                new: MonsterDetails {
+                    size: Some(BodySize::Large),
+                    ..MonsterDetails::default()
+                },
+            }],
+        )]
+    );
+    assert!(diff.affected_trees.is_empty());
+    assert!(!diff.is_empty());
+
+    let report = to_report(&diff, &old, &new, GameData::embedded().unwrap(), Locale::En);
+    assert_eq!(report, "Changed:\n  1 a\n    size: - -> Large\n");
+}
+
+#[test]
+fn test_diff_embedded_data_is_empty() {
+    let monsters = super::data::get_monster_data();
+    let diff = diff_monsters(monsters, monsters);
+    assert_eq!(diff, DatasetDiff::default());
+    assert!(diff.is_empty());
+}
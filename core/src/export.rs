@@ -0,0 +1,749 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use serde::Serialize;
+use unicode_width::UnicodeWidthStr;
+
+use super::data::{lookup_name, AreaId, FamilyId, GameData, Locale, MonsterId};
+use super::recipe::{is_scoutable, summarize_materials, Material, MonsterNode};
+
+struct NodeLabeler<'a> {
+    data: &'a GameData,
+    locale: Locale,
+    family_suffix: String,
+}
+
+impl<'a> NodeLabeler<'a> {
+    fn new(data: &'a GameData, locale: Locale) -> Self {
+        Self {
+            data,
+            locale,
+            family_suffix: data
+                .lang
+                .get("family_suffix")
+                .map(|t| t.get(locale).to_string())
+                .unwrap_or_default(),
+        }
+    }
+
+    fn family_label(&self, family: FamilyId) -> String {
+        let name = lookup_name(&self.data.families, &family, self.locale)
+            .unwrap_or_default()
+            .to_string();
+        match self.family_suffix.is_empty() {
+            true => name,
+            false => format!("{:} {:}", name, self.family_suffix),
+        }
+    }
+
+    fn label(&self, node: &MonsterNode) -> String {
+        match (node.data.monster_id, &node.data.spec) {
+            (Some(id), _) => self
+                .data
+                .monsters
+                .get(&id)
+                .map(|m| m.name.get(self.locale).to_string())
+                .unwrap_or_default(),
+            (None, Some(spec)) => self.family_label(spec.family),
+            (None, None) => "".to_string(),
+        }
+    }
+
+    fn family(&self, node: &MonsterNode) -> Option<FamilyId> {
+        match (node.data.monster_id, &node.data.spec) {
+            (Some(id), _) => self.data.monsters.get(&id).map(|m| m.family),
+            (None, Some(spec)) => Some(spec.family),
+            (None, None) => None,
+        }
+    }
+}
+
+fn escape_mermaid_label(label: &str) -> String {
+    label.replace('"', "#quot;")
+}
+
+fn escape_markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+/// Edges point from the fusion materials to the monster they produce.
+pub fn to_mermaid(root: Rc<RefCell<MonsterNode>>, data: &GameData, locale: Locale) -> String {
+    let labeler = NodeLabeler::new(data, locale);
+    let mut nodes: Vec<String> = vec![];
+    let mut edges: Vec<String> = vec![];
+    let mut families: BTreeMap<FamilyId, Vec<String>> = BTreeMap::new();
+    let mut stack: Vec<(Option<usize>, Rc<RefCell<MonsterNode>>)> = vec![(None, root)];
+
+    while let Some((parent, node)) = stack.pop() {
+        let index = nodes.len();
+        if let Some(family) = labeler.family(&node.borrow()) {
+            families
+                .entry(family)
+                .or_default()
+                .push(format!("n{:}", index));
+        }
+        nodes.push(format!(
+            "    n{:}[\"{:}\"]",
+            index,
+            escape_mermaid_label(&labeler.label(&node.borrow()))
+        ));
+        if let Some(parent) = parent {
+            edges.push(format!("    n{:} --> n{:}", index, parent));
+        }
+        stack.extend(
+            node.borrow()
+                .children
+                .iter()
+                .rev()
+                .map(|c| (Some(index), c.clone())),
+        );
+    }
+
+    // Color the nodes like the tree view
+    let styles = families
+        .iter()
+        .filter_map(|(family, ids)| data.family_meta.get(family).map(|meta| (meta, ids)))
+        .collect::<Vec<_>>();
+
+    let mut lines = vec!["flowchart LR".to_string()];
+    lines.extend(nodes);
+    lines.extend(edges);
+    lines.extend(styles.iter().map(|(meta, _)| {
+        format!(
+            "    classDef {:} fill:{:},color:#FFF",
+            meta.slug, meta.color
+        )
+    }));
+    lines.extend(
+        styles
+            .iter()
+            .map(|(meta, ids)| format!("    class {:} {:}", ids.join(","), meta.slug)),
+    );
+    lines.join("\n") + "\n"
+}
+
+pub fn to_markdown(root: Rc<RefCell<MonsterNode>>, data: &GameData, locale: Locale) -> String {
+    let labeler = NodeLabeler::new(data, locale);
+    let mut lines: Vec<String> = vec![];
+    let mut stack: Vec<(usize, Rc<RefCell<MonsterNode>>)> = vec![(0, root.clone())];
+
+    while let Some((depth, node)) = stack.pop() {
+        lines.push(format!(
+            "{:}- {:}",
+            "  ".repeat(depth),
+            labeler.label(&node.borrow())
+        ));
+        stack.extend(
+            node.borrow()
+                .children
+                .iter()
+                .rev()
+                .map(|c| (depth + 1, c.clone())),
+        );
+    }
+
+    lines.push("".to_string());
+    lines.push(format!(
+        "| {:} | {:} | {:} |",
+        data.text("material", locale),
+        data.text("rank", locale),
+        data.text("count", locale)
+    ));
+    lines.push("| --- | --- | --- |".to_string());
+
+    for summary in summarize_materials(&data.monsters, root) {
+        let (name, rank) = match &summary.material {
+            Material::Monster(id) => match data.monsters.get(id) {
+                Some(monster) => (monster.name.get(locale).to_string(), Some(monster.rank)),
+                None => (String::new(), None),
+            },
+            Material::Family { families, rank } => (
+                families
+                    .iter()
+                    .map(|&f| labeler.family_label(f))
+                    .collect::<Vec<String>>()
+                    .join(" / "),
+                *rank,
+            ),
+        };
+        let rank = rank
+            .and_then(|r| lookup_name(&data.ranks, &r, locale))
+            .unwrap_or_default();
+        lines.push(format!(
+            "| {:} | {:} | {:} |",
+            escape_markdown_cell(&name),
+            escape_markdown_cell(rank),
+            summary.count
+        ));
+    }
+
+    lines.join("\n") + "\n"
+}
+
+fn text_tree_lines(node: &Rc<RefCell<MonsterNode>>, labeler: &NodeLabeler) -> Vec<String> {
+    let label = labeler.label(&node.borrow());
+    let children = node.borrow().children.clone();
+    if children.is_empty() {
+        return vec![label];
+    }
+
+    let indent = " ".repeat(label.width());
+    let mut lines: Vec<String> = vec![];
+    let mut previous_height = 0;
+    for (i, child) in children.iter().enumerate() {
+        let is_first = i == 0;
+        let is_last = i + 1 == children.len();
+        let child_lines = text_tree_lines(child, labeler);
+
+        // Separate sibling subtrees which span several lines
+        if previous_height > 1 {
+            lines.push(format!("{:}  │", indent));
+        }
+        previous_height = child_lines.len();
+        for (j, line) in child_lines.iter().enumerate() {
+            let connector = match (j, is_first, is_last) {
+                (0, true, true) => format!("{:} ─── ", label),
+                (0, true, false) => format!("{:} ─┬─ ", label),
+                (0, false, false) => format!("{:}  ├─ ", indent),
+                (0, false, true) => format!("{:}  └─ ", indent),
+                (_, _, false) => format!("{:}  │  ", indent),
+                (_, _, true) => format!("{:}     ", indent),
+            };
+            lines.push(format!("{:}{:}", connector, line).trim_end().to_string());
+        }
+    }
+
+    lines
+}
+
+/// Labels are aligned by their display width, so CJK names take two columns per character.
+pub fn to_text_tree(root: Rc<RefCell<MonsterNode>>, data: &GameData, locale: Locale) -> String {
+    let labeler = NodeLabeler::new(data, locale);
+    text_tree_lines(&root, &labeler).join("\n") + "\n"
+}
+
+#[derive(Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct MonsterRow {
+    pub id: MonsterId,
+    pub name: String,
+    pub family: String,
+    pub rank: String,
+}
+
+pub fn get_monster_row(id: MonsterId, data: &GameData, locale: Locale) -> Option<MonsterRow> {
+    data.monsters.get(&id).map(|m| MonsterRow {
+        id,
+        name: m.name.get(locale).to_string(),
+        family: lookup_name(&data.families, &m.family, locale)
+            .unwrap_or_default()
+            .to_string(),
+        rank: lookup_name(&data.ranks, &m.rank, locale)
+            .unwrap_or_default()
+            .to_string(),
+    })
+}
+
+/// Unknown ids are skipped.
+pub fn get_monster_rows(ids: &[MonsterId], data: &GameData, locale: Locale) -> Vec<MonsterRow> {
+    ids.iter()
+        .filter_map(|&id| get_monster_row(id, data, locale))
+        .collect()
+}
+
+/// `id` is not set for the family nodes of a standard fusion.
+#[derive(Serialize, PartialEq, Debug)]
+pub struct TreeNodeRow {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<MonsterId>,
+    pub name: String,
+    pub family: String,
+    pub rank: String,
+    pub children: Vec<TreeNodeRow>,
+}
+
+fn get_tree_node_row(node: &Rc<RefCell<MonsterNode>>, labeler: &NodeLabeler) -> TreeNodeRow {
+    let node = node.borrow();
+    let data = labeler.data;
+    let rank = match (node.data.monster_id, &node.data.spec) {
+        (Some(id), _) => data.monsters.get(&id).map(|m| m.rank),
+        (None, Some(spec)) => Some(spec.rank),
+        (None, None) => None,
+    };
+    TreeNodeRow {
+        id: node.data.monster_id,
+        name: labeler.label(&node),
+        family: labeler
+            .family(&node)
+            .and_then(|f| lookup_name(&data.families, &f, labeler.locale))
+            .unwrap_or_default()
+            .to_string(),
+        rank: rank
+            .and_then(|r| lookup_name(&data.ranks, &r, labeler.locale))
+            .unwrap_or_default()
+            .to_string(),
+        children: node
+            .children
+            .iter()
+            .map(|c| get_tree_node_row(c, labeler))
+            .collect(),
+    }
+}
+
+pub fn get_tree_row(
+    root: Rc<RefCell<MonsterNode>>,
+    data: &GameData,
+    locale: Locale,
+) -> TreeNodeRow {
+    get_tree_node_row(&root, &NodeLabeler::new(data, locale))
+}
+
+pub fn to_json_tree(root: Rc<RefCell<MonsterNode>>, data: &GameData, locale: Locale) -> String {
+    serde_json::to_string_pretty(&get_tree_row(root, data, locale)).unwrap()
+}
+
+/// `id` is not set for the materials that accept any monster of the families.
+#[derive(Serialize, PartialEq, Debug)]
+pub struct MaterialRow {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<MonsterId>,
+    pub name: String,
+    pub family: String,
+    pub rank: String,
+    pub count: usize,
+    pub scoutable: bool,
+    pub areas: Vec<String>,
+}
+
+pub fn get_material_rows(
+    root: Rc<RefCell<MonsterNode>>,
+    data: &GameData,
+    locale: Locale,
+) -> Vec<MaterialRow> {
+    summarize_materials(&data.monsters, root)
+        .into_iter()
+        .map(|summary| {
+            let monster_id = match summary.material {
+                Material::Monster(id) => Some(id),
+                Material::Family { .. } => None,
+            };
+            let (monster, family_ids, rank) = match summary.material {
+                Material::Monster(id) => {
+                    let monster = data.monsters.get(&id);
+                    (
+                        monster,
+                        monster.map(|m| vec![m.family]).unwrap_or_default(),
+                        monster.map(|m| m.rank),
+                    )
+                }
+                Material::Family { families, rank } => (None, families, rank),
+            };
+            let mut area_ids = monster
+                .map(|m| m.habitats.keys().copied().collect::<Vec<AreaId>>())
+                .unwrap_or_default();
+            area_ids.sort();
+
+            MaterialRow {
+                id: monster_id,
+                name: monster
+                    .map(|m| m.name.get(locale).to_string())
+                    .unwrap_or_default(),
+                family: family_ids
+                    .iter()
+                    .map(|f| lookup_name(&data.families, f, locale).unwrap_or_default())
+                    .collect::<Vec<&str>>()
+                    .join(" / "),
+                rank: rank
+                    .and_then(|r| lookup_name(&data.ranks, &r, locale))
+                    .unwrap_or_default()
+                    .to_string(),
+                count: summary.count,
+                scoutable: monster.map(is_scoutable).unwrap_or(false),
+                areas: area_ids
+                    .iter()
+                    .map(|a| {
+                        lookup_name(&data.areas, a, locale)
+                            .unwrap_or_default()
+                            .to_string()
+                    })
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+fn escape_csv_field(field: &str) -> String {
+    match field.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{:}\"", field.replace('"', "\"\"")),
+        false => field.to_string(),
+    }
+}
+
+/// Multiple areas are joined with " / " in a single column.
+pub fn materials_to_csv(root: Rc<RefCell<MonsterNode>>, data: &GameData, locale: Locale) -> String {
+    let mut lines = vec!["name,family,rank,count,scoutable,areas".to_string()];
+    lines.extend(get_material_rows(root, data, locale).iter().map(|row| {
+        [
+            escape_csv_field(&row.name),
+            escape_csv_field(&row.family),
+            escape_csv_field(&row.rank),
+            row.count.to_string(),
+            row.scoutable.to_string(),
+            escape_csv_field(&row.areas.join(" / ")),
+        ]
+        .join(",")
+    }));
+    lines.join("\r\n") + "\r\n"
+}
+
+fn pad_to_width(text: &str, width: usize) -> String {
+    format!(
+        "{:}{:}",
+        text,
+        " ".repeat(width.saturating_sub(text.width()))
+    )
+}
+
+/// Family materials are named by their families.
+pub fn materials_to_text(
+    root: Rc<RefCell<MonsterNode>>,
+    data: &GameData,
+    locale: Locale,
+) -> String {
+    let rows = get_material_rows(root, data, locale)
+        .into_iter()
+        .map(|row| {
+            [
+                row.count.to_string(),
+                match row.name.is_empty() {
+                    true => row.family,
+                    false => row.name,
+                },
+                row.rank,
+                row.areas.join(" / "),
+            ]
+        })
+        .collect::<Vec<[String; 4]>>();
+    let widths = (0..3)
+        .map(|i| rows.iter().map(|row| row[i].width()).max().unwrap_or(0))
+        .collect::<Vec<usize>>();
+
+    rows.iter()
+        .map(|row| {
+            let mut columns = widths
+                .iter()
+                .zip(row.iter())
+                .map(|(&width, column)| pad_to_width(column, width))
+                .collect::<Vec<String>>();
+            columns.push(row[3].clone());
+            columns.join("  ").trim_end().to_string() + "\n"
+        })
+        .collect()
+}
+
+pub fn materials_to_json(
+    root: Rc<RefCell<MonsterNode>>,
+    data: &GameData,
+    locale: Locale,
+) -> String {
+    serde_json::to_string_pretty(&get_material_rows(root, data, locale)).unwrap()
+}
+
+#[cfg(test)]
+fn build_test_tree() -> (Rc<RefCell<MonsterNode>>, GameData) {
+    use super::data::{Monster, MonsterDetails, MonsterId, Parent, RankId};
+    use super::recipe::MonsterTreeBuilder;
+    use std::collections::HashMap;
+
+    /*
+     * 0 +- 1 +- スライム 系
+     *   |    +- スライム 系
+     *   |
+     *   +- 2
+     */
+    let lut: HashMap<MonsterId, Monster> = HashMap::from([
+        (
+            MonsterId(0),
+            Monster {
+                name: "a".into(),
+                rank: RankId(2),
+                family: FamilyId(1),
+                parents: vec![vec![
+                    Parent {
+                        monster: Some(MonsterId(1)),
+                        family: None,
+                    },
+                    Parent {
+                        monster: Some(MonsterId(2)),
+                        family: None,
+                    },
+                ]],
+                habitats: HashMap::new(),
+                details: MonsterDetails::default(),
+            },
+        ),
+        (
+            MonsterId(1),
+            Monster {
+                name: "b".into(),
+                rank: RankId(3),
+                family: FamilyId(0),
+                parents: vec![vec![
+                    Parent {
+                        monster: None,
+                        family: Some(FamilyId(0)),
+                    },
+                    Parent {
+                        monster: None,
+                        family: Some(FamilyId(0)),
+                    },
+                ]],
+                habitats: HashMap::new(),
+                details: MonsterDetails::default(),
+            },
+        ),
+        (
+            MonsterId(2),
+            Monster {
+                name: "c\"|".into(),
+                rank: RankId(3),
+                family: FamilyId(0),
+                parents: vec![],
+                habitats: HashMap::new(),
+                details: MonsterDetails::default(),
+            },
+        ),
+    ]);
+
+    let tree = MonsterTreeBuilder::new(lut.clone()).build(MonsterId(0));
+    let data = GameData {
+        monsters: lut,
+        ..GameData::embedded().unwrap().clone()
+    };
+    (Rc::new(RefCell::new(tree)), data)
+}
+
+#[test]
+fn test_to_mermaid() {
+    let (tree, data) = build_test_tree();
+    let expected = [
+        "flowchart LR",
+        "    n0[\"a\"]",
+        "    n1[\"b\"]",
+        "    n2[\"スライム 系\"]",
+        "    n3[\"スライム 系\"]",
+        "    n4[\"c#quot;|\"]",
+        "    n1 --> n0",
+        "    n2 --> n1",
+        "    n3 --> n1",
+        "    n4 --> n0",
+        "    classDef slime fill:#1E90FF,color:#FFF",
+        "    classDef dragon fill:#FF00FF,color:#FFF",
+        "    class n1,n2,n3,n4 slime",
+        "    class n0 dragon",
+        "",
+    ]
+    .join("\n");
+    assert_eq!(to_mermaid(tree.clone(), &data, Locale::Ja), expected);
+
+    let expected = [
+        "flowchart LR",
+        "    n0[\"a\"]",
+        "    n1[\"b\"]",
+        "    n2[\"Slime\"]",
+        "    n3[\"Slime\"]",
+        "    n4[\"c#quot;|\"]",
+        "    n1 --> n0",
+        "    n2 --> n1",
+        "    n3 --> n1",
+        "    n4 --> n0",
+        "    classDef slime fill:#1E90FF,color:#FFF",
+        "    classDef dragon fill:#FF00FF,color:#FFF",
+        "    class n1,n2,n3,n4 slime",
+        "    class n0 dragon",
+        "",
+    ]
+    .join("\n");
+    assert_eq!(to_mermaid(tree, &data, Locale::En), expected);
+}
+
+#[test]
+fn test_to_markdown() {
+    let (tree, data) = build_test_tree();
+    let expected = [
+        "- a",
+        "  - b",
+        "    - スライム 系",
+        "    - スライム 系",
+        "  - c\"|",
+        "",
+        "| 素材 | ランク | 数 |",
+        "| --- | --- | --- |",
+        "| c\"\\| | B | 1 |",
+        "| スライム 系 / スライム 系 | B | 1 |",
+        "",
+    ]
+    .join("\n");
+    assert_eq!(to_markdown(tree, &data, Locale::Ja), expected);
+}
+
+#[test]
+fn test_to_text_tree() {
+    use super::data::MonsterId;
+
+    let (tree, mut data) = build_test_tree();
+    let expected = [
+        "a ─┬─ b ─┬─ スライム 系",
+        "   │     └─ スライム 系",
+        "   │",
+        "   └─ c\"|",
+        "",
+    ]
+    .join("\n");
+    assert_eq!(to_text_tree(tree.clone(), &data, Locale::Ja), expected);
+
+    data.monsters.get_mut(&MonsterId(0)).unwrap().name = "スラ".into();
+    let expected = [
+        "スラ ─┬─ b ─┬─ スライム 系",
+        "      │     └─ スライム 系",
+        "      │",
+        "      └─ c\"|",
+        "",
+    ]
+    .join("\n");
+    assert_eq!(to_text_tree(tree, &data, Locale::Ja), expected);
+}
+
+#[test]
+fn test_materials_to_csv() {
+    use super::data::{Habitat, MonsterId, SeasonHabitat, SeasonId};
+
+    let (tree, mut data) = build_test_tree();
+    data.monsters.get_mut(&MonsterId(2)).unwrap().habitats = vec![(
+        AreaId(5),
+        Habitat {
+            seasons: vec![(SeasonId(0), SeasonHabitat::new(&[true, false]).unwrap())]
+                .into_iter()
+                .collect(),
+        },
+    )]
+    .into_iter()
+    .collect();
+
+    let expected = [
+        "name,family,rank,count,scoutable,areas",
+        "\"c\"\"|\",スライム,B,1,true,人間界・平原",
+        ",スライム / スライム,B,1,false,",
+        "",
+    ]
+    .join("\r\n");
+    assert_eq!(materials_to_csv(tree.clone(), &data, Locale::Ja), expected);
+
+    let expected = [
+        "name,family,rank,count,scoutable,areas",
+        "\"c\"\"|\",Slime,B,1,true,人間界・平原",
+        ",Slime / Slime,B,1,false,",
+        "",
+    ]
+    .join("\r\n");
+    assert_eq!(materials_to_csv(tree, &data, Locale::En), expected);
+}
+
+#[test]
+fn test_materials_to_json() {
+    let (tree, data) = build_test_tree();
+    let actual: serde_json::Value =
+        serde_json::from_str(&materials_to_json(tree, &data, Locale::Ja)).unwrap();
+    let expected = serde_json::json!([
+        {
+            "id": 2,
+            "name": "c\"|",
+            "family": "スライム",
+            "rank": "B",
+            "count": 1,
+            "scoutable": false,
+            "areas": [],
+        },
+        {
+            "name": "",
+            "family": "スライム / スライム",
+            "rank": "B",
+            "count": 1,
+            "scoutable": false,
+            "areas": [],
+        },
+    ]);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn test_to_json_tree() {
+    let (tree, data) = build_test_tree();
+    let actual: serde_json::Value =
+        serde_json::from_str(&to_json_tree(tree, &data, Locale::En)).unwrap();
+    let family = serde_json::json!({
+        "name": "Slime",
+        "family": "Slime",
+        "rank": "B",
+        "children": [],
+    });
+    let expected = serde_json::json!({
+        "id": 0,
+        "name": "a",
+        "family": "Dragon",
+        "rank": "A",
+        "children": [
+            {
+                "id": 1,
+                "name": "b",
+                "family": "Slime",
+                "rank": "B",
+                "children": [family.clone(), family],
+            },
+            {
+                "id": 2,
+                "name": "c\"|",
+                "family": "Slime",
+                "rank": "B",
+                "children": [],
+            },
+        ],
+    });
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn test_materials_to_text() {
+    use super::data::{Habitat, MonsterId, SeasonHabitat, SeasonId};
+    use std::collections::HashMap;
+
+    let (tree, mut data) = build_test_tree();
+    data.monsters.get_mut(&MonsterId(2)).unwrap().habitats = HashMap::from([(
+        AreaId(5),
+        Habitat {
+            seasons: HashMap::from([(SeasonId(0), SeasonHabitat::new(&[true, false]).unwrap())]),
+        },
+    )]);
+
+    let expected = [
+        "1  c\"|                  B  人間界・平原",
+        "1  スライム / スライム  B",
+        "",
+    ]
+    .join("\n");
+    assert_eq!(materials_to_text(tree, &data, Locale::Ja), expected);
+}
+
+#[test]
+fn test_get_monster_rows() {
+    let (_, data) = build_test_tree();
+    assert_eq!(
+        get_monster_rows(&[MonsterId(1), MonsterId(9)], &data, Locale::En),
+        vec![MonsterRow {
+            id: MonsterId(1),
+            name: "b".to_string(),
+            family: "Slime".to_string(),
+            rank: "B".to_string(),
+        }]
+    );
+}
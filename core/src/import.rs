@@ -0,0 +1,636 @@
+//! Conversion of the shared spreadsheet into `monster.json` and `area.json`.
+//!
+//! The sheet has one header row and one row per monster. Column names are matched
+//! case-insensitively:
+//!
+//! - `id` (optional): the monster id. When it is empty, the id of the monster with the same
+//!   name in the current `monster.json` is kept, and new monsters are numbered after the
+//!   largest id ever used.
+//! - `name`, `name_en` (optional): the Japanese and English names.
+//! - `rank`, `family`: names from `rank.json` and `family.json`.
+//! - `parents` (optional): recipes separated by `/`, parents of a recipe separated by `+`.
+//!   A family is written as its name followed by `系`, e.g. `スライム系 + ドラキー`.
+//! - One column per season, named as in `season.json`: the areas where the monster can be
+//!   scouted in that season, separated by `,` or `、`. An area name may be followed by its
+//!   habitat flags as written in `monster.json`, e.g. `森 [false]`; without them the area gets
+//!   `[true, false]`, the flags of most habitats in the embedded data.
+//!   Areas missing from `area.json` are added to it.
+//!
+//! The sheet has no columns for stats, size, traits and skills, so a monster already in
+//! `monster.json` keeps them.
+
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::hash::Hash;
+
+#[cfg(test)]
+use super::data::MonsterDetails;
+use super::data::{
+    AreaId, FamilyId, Habitat, LocalizedName, Monster, MonsterId, Parent, RankId, SeasonHabitat,
+    SeasonId,
+};
+use super::search::{normalize, SearchIndex};
+
+pub struct ImportContext {
+    pub monsters: HashMap<MonsterId, Monster>,
+    pub families: HashMap<FamilyId, LocalizedName>,
+    pub ranks: HashMap<RankId, LocalizedName>,
+    pub areas: HashMap<AreaId, LocalizedName>,
+    pub seasons: HashMap<SeasonId, LocalizedName>,
+}
+
+/// Rows are numbered as in a spreadsheet, so the header is row 1.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ImportIssue {
+    pub row: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ImportIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "row {:}: {:}", self.row, self.message)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct ImportResult {
+    pub monsters: HashMap<MonsterId, Monster>,
+    pub areas: HashMap<AreaId, LocalizedName>,
+    pub new_areas: Vec<AreaId>,
+}
+
+const HEADER_ROW: usize = 1;
+const FIRST_MONSTER_ID: MonsterId = MonsterId(1);
+
+enum Column {
+    Id,
+    Name,
+    NameEn,
+    Rank,
+    Family,
+    Parents,
+    Season(SeasonId),
+}
+
+fn name_index<K: Copy + Hash + Eq>(table: &HashMap<K, LocalizedName>) -> HashMap<String, K> {
+    table
+        .iter()
+        .flat_map(|(&id, name)| {
+            [(normalize(&name.ja), id)]
+                .into_iter()
+                .chain(name.en.as_ref().map(|en| (normalize(en), id)))
+        })
+        .collect()
+}
+
+fn issue(row: usize, message: String) -> ImportIssue {
+    ImportIssue { row, message }
+}
+
+fn parse_header(
+    header: &[String],
+    seasons: &HashMap<String, SeasonId>,
+) -> Result<Vec<Column>, Vec<ImportIssue>> {
+    let mut columns = vec![];
+    let mut issues = vec![];
+    for name in header.iter() {
+        let column = match name.trim().to_lowercase().as_str() {
+            "id" => Column::Id,
+            "name" => Column::Name,
+            "name_en" => Column::NameEn,
+            "rank" => Column::Rank,
+            "family" => Column::Family,
+            "parents" => Column::Parents,
+            _ => match seasons.get(&normalize(name)) {
+                Some(&season) => Column::Season(season),
+                None => {
+                    issues.push(issue(HEADER_ROW, format!("unknown column \"{:}\"", name)));
+                    continue;
+                }
+            },
+        };
+        columns.push(column);
+    }
+    for (required, found) in [
+        ("name", columns.iter().any(|c| matches!(c, Column::Name))),
+        ("rank", columns.iter().any(|c| matches!(c, Column::Rank))),
+        (
+            "family",
+            columns.iter().any(|c| matches!(c, Column::Family)),
+        ),
+    ] {
+        if !found {
+            issues.push(issue(
+                HEADER_ROW,
+                format!("missing column \"{:}\"", required),
+            ));
+        }
+    }
+    match issues.is_empty() {
+        true => Ok(columns),
+        false => Err(issues),
+    }
+}
+
+#[derive(Default)]
+struct Row<'a> {
+    number: usize,
+    id: Option<&'a str>,
+    name: &'a str,
+    name_en: Option<&'a str>,
+    rank: &'a str,
+    family: &'a str,
+    parents: &'a str,
+    seasons: Vec<(SeasonId, &'a str)>,
+}
+
+fn split_row<'a>(number: usize, columns: &[Column], cells: &'a [String]) -> Row<'a> {
+    let mut row = Row {
+        number,
+        ..Row::default()
+    };
+    for (column, cell) in columns.iter().zip(cells.iter()) {
+        let cell = cell.trim();
+        let optional = match cell.is_empty() {
+            true => None,
+            false => Some(cell),
+        };
+        match column {
+            Column::Id => row.id = optional,
+            Column::Name => row.name = cell,
+            Column::NameEn => row.name_en = optional,
+            Column::Rank => row.rank = cell,
+            Column::Family => row.family = cell,
+            Column::Parents => row.parents = cell,
+            Column::Season(season) => row.seasons.push((*season, cell)),
+        }
+    }
+    row
+}
+
+/// Give every row an id: the one in the sheet, the current one for a known name,
+/// or a new one after the largest id in use.
+fn assign_ids(
+    rows: &[Row],
+    current: &HashMap<MonsterId, Monster>,
+    issues: &mut Vec<ImportIssue>,
+) -> Vec<Option<MonsterId>> {
+    let mut ids = vec![None; rows.len()];
+    let mut used: HashMap<MonsterId, usize> = HashMap::new();
+    for (i, row) in rows.iter().enumerate() {
+        if let Some(id) = row.id {
+            match id.parse::<usize>() {
+                Ok(id) => match used.get(&MonsterId(id)) {
+                    None => {
+                        used.insert(MonsterId(id), row.number);
+                        ids[i] = Some(MonsterId(id));
+                    }
+                    Some(other) => issues.push(issue(
+                        row.number,
+                        format!("id {:} is already used by row {:}", id, other),
+                    )),
+                },
+                Err(_) => issues.push(issue(row.number, format!("invalid id \"{:}\"", id))),
+            }
+        }
+    }
+
+    let current_ids = current
+        .iter()
+        .map(|(&id, monster)| (normalize(&monster.name.ja), id))
+        .collect::<HashMap<String, MonsterId>>();
+    for (i, row) in rows.iter().enumerate() {
+        if row.id.is_some() {
+            continue;
+        }
+        if let Some(&id) = current_ids.get(&normalize(row.name)) {
+            if let Entry::Vacant(entry) = used.entry(id) {
+                entry.insert(row.number);
+                ids[i] = Some(id);
+            }
+        }
+    }
+
+    // Ids of monsters dropped from the sheet are not reused either
+    let mut next = match current.keys().chain(used.keys()).max() {
+        Some(max) => max.0 + 1,
+        None => FIRST_MONSTER_ID.0,
+    };
+    for (i, row) in rows.iter().enumerate() {
+        if row.id.is_none() && ids[i].is_none() {
+            ids[i] = Some(MonsterId(next));
+            next += 1;
+        }
+    }
+    ids
+}
+
+fn resolve_parents(
+    row: &Row,
+    monsters: &HashMap<String, MonsterId>,
+    families: &HashMap<String, FamilyId>,
+    suggestions: &SearchIndex,
+    sheet: &HashMap<MonsterId, Monster>,
+    issues: &mut Vec<ImportIssue>,
+) -> Vec<Vec<Parent>> {
+    let mut recipes = vec![];
+    for recipe in row.parents.split('/').filter(|r| !r.trim().is_empty()) {
+        let mut parents = vec![];
+        for name in recipe.split('+').map(str::trim) {
+            let parent = match name.strip_suffix('系') {
+                Some(family) => families.get(&normalize(family)).map(|&id| Parent {
+                    monster: None,
+                    family: Some(id),
+                }),
+                None => monsters.get(&normalize(name)).map(|&id| Parent {
+                    monster: Some(id),
+                    family: None,
+                }),
+            };
+            match parent {
+                Some(parent) => parents.push(parent),
+                None if name.is_empty() => issues.push(issue(
+                    row.number,
+                    format!("empty parent in \"{:}\"", recipe.trim()),
+                )),
+                None => {
+                    let hint = suggestions
+                        .search(name, 1)
+                        .first()
+                        .and_then(|hit| sheet.get(&hit.monster))
+                        .map(|m| format!(" (did you mean \"{:}\"?)", m.name.ja))
+                        .unwrap_or_default();
+                    issues.push(issue(
+                        row.number,
+                        format!("unknown parent \"{:}\"{:}", name, hint),
+                    ));
+                }
+            }
+        }
+        recipes.push(parents);
+    }
+    recipes
+}
+
+/// Split `森 [true, false]` into the area name and its habitat flags.
+fn parse_area_token(token: &str) -> Result<(&str, SeasonHabitat), String> {
+    let Some((name, flags)) = token.strip_suffix(']').and_then(|t| t.rsplit_once('[')) else {
+        return Ok((token, SeasonHabitat::new(&[true, false]).unwrap()));
+    };
+    let flags = flags
+        .split(',')
+        .map(|flag| match flag.trim() {
+            "true" => Ok(true),
+            "false" => Ok(false),
+            _ => Err(format!("invalid habitat flags in \"{:}\"", token)),
+        })
+        .collect::<Result<Vec<bool>, String>>()?;
+    let habitat = SeasonHabitat::new(&flags).map_err(|e| format!("{:} in \"{:}\"", e, token))?;
+    Ok((name.trim(), habitat))
+}
+
+/// Nothing is returned unless every row resolves.
+pub fn import_rows(
+    header: &[String],
+    rows: &[Vec<String>],
+    context: &ImportContext,
+) -> Result<ImportResult, Vec<ImportIssue>> {
+    let columns = parse_header(header, &name_index(&context.seasons))?;
+    let rows = rows
+        .iter()
+        .enumerate()
+        .map(|(i, cells)| split_row(HEADER_ROW + 1 + i, &columns, cells))
+        .collect::<Vec<Row>>();
+
+    let mut issues = vec![];
+    let ids = assign_ids(&rows, &context.monsters, &mut issues);
+    let ranks = name_index(&context.ranks);
+    let families = name_index(&context.families);
+
+    // Names first, so that parents can refer to any row of the sheet
+    let mut monsters: HashMap<MonsterId, Monster> = HashMap::new();
+    let mut monster_names: HashMap<String, MonsterId> = HashMap::new();
+    for (row, id) in rows.iter().zip(ids.iter()) {
+        let Some(id) = *id else { continue };
+        if row.name.is_empty() {
+            issues.push(issue(row.number, "empty name".to_string()));
+            continue;
+        }
+        let rank = ranks.get(&normalize(row.rank)).copied();
+        if rank.is_none() {
+            issues.push(issue(row.number, format!("unknown rank \"{:}\"", row.rank)));
+        }
+        let family = families.get(&normalize(row.family)).copied();
+        if family.is_none() {
+            issues.push(issue(
+                row.number,
+                format!("unknown family \"{:}\"", row.family),
+            ));
+        }
+        let name = LocalizedName {
+            ja: row.name.to_string(),
+            en: row.name_en.map(|en| en.to_string()),
+        };
+        for key in [Some(row.name), row.name_en].into_iter().flatten() {
+            if let Some(other) = monster_names.insert(normalize(key), id) {
+                if other != id {
+                    issues.push(issue(row.number, format!("duplicate name \"{:}\"", key)));
+                }
+            }
+        }
+        if let (Some(rank), Some(family)) = (rank, family) {
+            let monster = Monster {
+                name,
+                rank,
+                family,
+                parents: vec![],
+                habitats: HashMap::new(),
+                details: context
+                    .monsters
+                    .get(&id)
+                    .map(|monster| monster.details.clone())
+                    .unwrap_or_default(),
+            };
+            monsters.insert(id, monster);
+        }
+    }
+
+    let suggestions = SearchIndex::new(&monsters);
+    let mut areas = context.areas.clone();
+    let mut area_names = name_index(&areas);
+    let mut new_areas = vec![];
+    for (row, id) in rows.iter().zip(ids.iter()) {
+        let parents = resolve_parents(
+            row,
+            &monster_names,
+            &families,
+            &suggestions,
+            &monsters,
+            &mut issues,
+        );
+
+        let mut habitats: HashMap<AreaId, Habitat> = HashMap::new();
+        for &(season, cell) in row.seasons.iter() {
+            for token in cell.split([',', '、']).map(str::trim) {
+                if token.is_empty() {
+                    continue;
+                }
+                let (name, habitat) = match parse_area_token(token) {
+                    Ok(area) => area,
+                    Err(message) => {
+                        issues.push(issue(row.number, message));
+                        continue;
+                    }
+                };
+                let area = match area_names.get(&normalize(name)) {
+                    Some(&area) => area,
+                    None => {
+                        let area = AreaId(areas.keys().map(|a| a.0 + 1).max().unwrap_or(0));
+                        areas.insert(area, LocalizedName::from(name));
+                        area_names.insert(normalize(name), area);
+                        new_areas.push(area);
+                        area
+                    }
+                };
+                habitats
+                    .entry(area)
+                    .or_insert_with(|| Habitat {
+                        seasons: HashMap::new(),
+                    })
+                    .seasons
+                    .insert(season, habitat);
+            }
+        }
+
+        if let Some(monster) = id.and_then(|id| monsters.get_mut(&id)) {
+            monster.parents = parents;
+            monster.habitats = habitats;
+        }
+    }
+
+    match issues.is_empty() {
+        true => Ok(ImportResult {
+            monsters,
+            areas,
+            new_areas,
+        }),
+        false => {
+            issues.sort_by_key(|i| i.row);
+            Err(issues)
+        }
+    }
+}
+
+/// The keys are written in the order of their ids, so that converting the same sheet twice
+/// gives the same file.
+pub fn to_data_json<K: Ord + serde::Serialize, V: serde::Serialize>(
+    table: &HashMap<K, V>,
+) -> String {
+    let table = table.iter().collect::<BTreeMap<&K, &V>>();
+    serde_json::to_string_pretty(&table).unwrap()
+}
+
+#[cfg(test)]
+fn test_context() -> ImportContext {
+    ImportContext {
+        monsters: HashMap::from([(
+            MonsterId(5),
+            Monster {
+                name: "スライム".into(),
+                rank: RankId(0),
+                family: FamilyId(0),
+                parents: vec![],
+                habitats: HashMap::new(),
+                details: MonsterDetails::default(),
+            },
+        )]),
+        families: HashMap::from([
+            (
+                FamilyId(0),
+                LocalizedName {
+                    ja: "スライム".to_string(),
+                    en: Some("Slime".to_string()),
+                },
+            ),
+            (FamilyId(1), LocalizedName::from("ドラゴン")),
+        ]),
+        ranks: HashMap::from([(RankId(0), "F".into()), (RankId(1), "E".into())]),
+        areas: HashMap::from([(AreaId(0), "平原".into())]),
+        seasons: HashMap::from([
+            (
+                SeasonId(0),
+                LocalizedName {
+                    ja: "春".to_string(),
+                    en: Some("Spring".to_string()),
+                },
+            ),
+            (SeasonId(1), "夏".into()),
+        ]),
+    }
+}
+
+#[cfg(test)]
+fn to_cells(line: &str) -> Vec<String> {
+    line.split('\t').map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn test_import_rows() {
+    let header = to_cells("id\tname\tname_en\trank\tfamily\tparents\tSpring\t夏");
+    let rows = [
+        "\tすらいむ\tSlime\tF\tSlime\t\t平原\t平原 [true]",
+        "\tドラキー\t\tF\tドラゴン\t\t\t森[false]",
+        "9\tスライムナイト\t\tE\tスライム\tスライム + ドラキー / スライム系 + ドラゴン系\t\t",
+    ]
+    .map(to_cells);
+
+    let result = import_rows(&header, &rows, &test_context()).unwrap();
+    assert_eq!(result.new_areas, vec![AreaId(1)]);
+    assert_eq!(result.areas[&AreaId(1)], LocalizedName::from("森"));
+
+    // The known name keeps its id, the new one is numbered after the largest id
+    assert_eq!(result.monsters.len(), 3);
+    let slime = &result.monsters[&MonsterId(5)];
+    assert_eq!(slime.name.en.as_deref(), Some("Slime"));
+    assert_eq!(
+        slime.habitats[&AreaId(0)].seasons,
+        HashMap::from([
+            (SeasonId(0), SeasonHabitat::new(&[true, false]).unwrap()),
+            (SeasonId(1), SeasonHabitat::new(&[true]).unwrap()),
+        ])
+    );
+    let dracky = &result.monsters[&MonsterId(10)];
+    assert_eq!(dracky.family, FamilyId(1));
+    assert_eq!(
+        dracky.habitats[&AreaId(1)].seasons[&SeasonId(1)],
+        SeasonHabitat::new(&[false]).unwrap()
+    );
+    let knight = &result.monsters[&MonsterId(9)];
+    assert_eq!(knight.rank, RankId(1));
+    assert_eq!(
+        knight.parents,
+        vec![
+            vec![
+                Parent {
+                    monster: Some(MonsterId(5)),
+                    family: None,
+                },
+                Parent {
+                    monster: Some(MonsterId(10)),
+                    family: None,
+                },
+            ],
+            vec![
+                Parent {
+                    monster: None,
+                    family: Some(FamilyId(0)),
+                },
+                Parent {
+                    monster: None,
+                    family: Some(FamilyId(1)),
+                },
+            ],
+        ]
+    );
+
+    // Ids of the monsters missing from the sheet are not reused
+    let rows = ["\tドラキー\t\tF\tドラゴン\t\t\t"].map(to_cells);
+    let result = import_rows(&header, &rows, &test_context()).unwrap();
+    assert_eq!(
+        result.monsters.keys().collect::<Vec<_>>(),
+        vec![&MonsterId(6)]
+    );
+}
+
+#[test]
+fn test_import_issues() {
+    let context = test_context();
+    assert_eq!(
+        import_rows(&to_cells("name\trank\tcolor"), &[], &context).unwrap_err(),
+        vec![
+            issue(1, "unknown column \"color\"".to_string()),
+            issue(1, "missing column \"family\"".to_string()),
+        ]
+    );
+
+    let header = to_cells("id\tname\trank\tfamily\tparents\t夏");
+    let rows = [
+        "1\tスライム\tF\tスライム\t\t",
+        "1\tドラキー\tS\tドラゴン\t\t森 [yes]",
+        "\tスライムナイト\tF\t魔獣\tスライム + ドラギー\t森 []",
+    ]
+    .map(to_cells);
+    assert_eq!(
+        import_rows(&header, &rows, &context).unwrap_err(),
+        vec![
+            issue(3, "id 1 is already used by row 2".to_string()),
+            issue(3, "invalid habitat flags in \"森 [yes]\"".to_string()),
+            issue(4, "unknown family \"魔獣\"".to_string()),
+            issue(4, "unknown parent \"ドラギー\"".to_string()),
+            issue(4, "invalid habitat flags in \"森 []\"".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_import_keeps_details() {
+    use super::data::{
+        get_area_data, get_family_data, get_monster_data, get_rank_data, get_season_data,
+        BaseStats, BodySize,
+    };
+
+    let mut context = ImportContext {
+        monsters: get_monster_data().clone(),
+        families: get_family_data().clone(),
+        ranks: get_rank_data().clone(),
+        areas: get_area_data().clone(),
+        seasons: get_season_data().clone(),
+    };
+    let details = MonsterDetails {
+        stats: Some(BaseStats {
+            hp: 10,
+            mp: 5,
+            attack: 8,
+            defense: 6,
+            agility: 7,
+            wisdom: 4,
+        }),
+        size: Some(BodySize::Normal),
+        traits: vec!["スライムボディ".into()],
+        skills: vec!["ホイミ".into()],
+    };
+    let monster = context.monsters.get_mut(&FIRST_MONSTER_ID).unwrap();
+    monster.details = details.clone();
+    let row = vec![
+        monster.name.ja.clone(),
+        context.ranks[&monster.rank].ja.clone(),
+        context.families[&monster.family].ja.clone(),
+    ];
+
+    let result = import_rows(&to_cells("name\trank\tfamily"), &[row], &context).unwrap();
+    assert_eq!(result.monsters[&FIRST_MONSTER_ID].details, details);
+}
+
+#[test]
+fn test_embedded_round_trip() {
+    use super::data::{get_area_data, get_monster_data, parse_id_name_json, parse_monster_json};
+
+    let parse = |json: &str| serde_json::from_str::<serde_json::Value>(json).unwrap();
+
+    let monsters = get_monster_data();
+    let json = to_data_json(monsters);
+    assert_eq!(parse(&json), parse(include_str!("data/monster.json")));
+    assert_eq!(
+        parse_monster_json("monster.json", &json).unwrap(),
+        *monsters
+    );
+
+    let areas = get_area_data();
+    let json = to_data_json(areas);
+    assert_eq!(parse(&json), parse(include_str!("data/area.json")));
+    assert_eq!(
+        parse_id_name_json::<AreaId>("area.json", &json).unwrap(),
+        *areas
+    );
+}
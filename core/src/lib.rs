@@ -0,0 +1,10 @@
+//! Dataset loading and the fusion recipe engine, without any dependency on the browser.
+//! The Yew frontend and the native tools in `src/bin` are built on it.
+
+pub mod data;
+pub mod diff;
+pub mod export;
+pub mod import;
+pub mod lint;
+pub mod recipe;
+pub mod search;
@@ -0,0 +1,421 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use super::data::{
+    AreaId, BodySize, FamilyId, GameData, Locale, Monster, MonsterId, Parent, RankId, SeasonId,
+};
+use super::recipe::{is_large_recipe, is_scoutable};
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// Errors break lookups in the recipe engine or the views, warnings are suspicious entries.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum LintIssue {
+    UnknownParentMonster {
+        monster: MonsterId,
+        parent: MonsterId,
+    },
+    UnknownFamily {
+        monster: MonsterId,
+        family: FamilyId,
+    },
+    UnknownRank {
+        monster: MonsterId,
+        rank: RankId,
+    },
+    UnknownArea {
+        monster: MonsterId,
+        area: AreaId,
+    },
+    UnknownSeason {
+        monster: MonsterId,
+        area: AreaId,
+        season: SeasonId,
+    },
+    /// Several monsters share a name, so only one of them can be searched by name.
+    DuplicateName {
+        name: String,
+        monsters: Vec<MonsterId>,
+    },
+    DuplicateRecipe {
+        monster: MonsterId,
+        recipe: usize,
+    },
+    SelfReferencingRecipe {
+        monster: MonsterId,
+        recipe: usize,
+    },
+    /// A four-parent recipe of a monster recorded as normal size.
+    FourParentNormalSize {
+        monster: MonsterId,
+        recipe: usize,
+    },
+    /// The monster is neither scoutable nor fusible from scoutable monsters.
+    Unreachable {
+        monster: MonsterId,
+    },
+}
+
+impl LintIssue {
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::SelfReferencingRecipe { .. }
+            | Self::FourParentNormalSize { .. }
+            | Self::Unreachable { .. } => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+}
+
+impl fmt::Display for LintIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownParentMonster { monster, parent } => write!(
+                f,
+                "monster {:}: parent monster {:} does not exist",
+                monster, parent
+            ),
+            Self::UnknownFamily { monster, family } => {
+                write!(f, "monster {:}: family {:} does not exist", monster, family)
+            }
+            Self::UnknownRank { monster, rank } => {
+                write!(f, "monster {:}: rank {:} does not exist", monster, rank)
+            }
+            Self::UnknownArea { monster, area } => {
+                write!(f, "monster {:}: area {:} does not exist", monster, area)
+            }
+            Self::UnknownSeason {
+                monster,
+                area,
+                season,
+            } => write!(
+                f,
+                "monster {:}: season {:} in area {:} does not exist",
+                monster, season, area
+            ),
+            Self::DuplicateName { name, monsters } => {
+                write!(f, "monsters {:?} share the name \"{:}\"", monsters, name)
+            }
+            Self::DuplicateRecipe { monster, recipe } => write!(
+                f,
+                "monster {:}: recipe #{:} duplicates an earlier recipe",
+                monster, recipe
+            ),
+            Self::SelfReferencingRecipe { monster, recipe } => write!(
+                f,
+                "monster {:}: recipe #{:} requires the monster itself",
+                monster, recipe
+            ),
+            Self::FourParentNormalSize { monster, recipe } => write!(
+                f,
+                "monster {:}: recipe #{:} has four parents, but the monster is recorded as normal size",
+                monster, recipe
+            ),
+            Self::Unreachable { monster } => write!(
+                f,
+                "monster {:}: neither scoutable nor reachable by fusion",
+                monster
+            ),
+        }
+    }
+}
+
+fn recipe_key(parents: &[Parent]) -> Vec<(Option<MonsterId>, Option<FamilyId>)> {
+    let mut key = parents
+        .iter()
+        .map(|p| (p.monster, p.family))
+        .collect::<Vec<_>>();
+    key.sort();
+    key
+}
+
+fn find_obtainable_monsters(monsters: &HashMap<MonsterId, Monster>) -> HashSet<MonsterId> {
+    let mut obtainable: HashSet<MonsterId> = monsters
+        .iter()
+        .filter(|(_, m)| is_scoutable(m))
+        .map(|(&id, _)| id)
+        .collect();
+
+    // Expand with fusions until no more monster becomes obtainable
+    loop {
+        let families: HashSet<FamilyId> = obtainable
+            .iter()
+            .filter_map(|id| monsters.get(id))
+            .map(|m| m.family)
+            .collect();
+        let fusible: Vec<MonsterId> = monsters
+            .iter()
+            .filter(|(id, _)| !obtainable.contains(id))
+            .filter(|(_, m)| {
+                m.parents.iter().any(|ps| {
+                    ps.iter().all(|p| match (p.monster, p.family) {
+                        (Some(id), _) => obtainable.contains(&id),
+                        (None, Some(family)) => families.contains(&family),
+                        (None, None) => false,
+                    })
+                })
+            })
+            .map(|(&id, _)| id)
+            .collect();
+        if fusible.is_empty() {
+            break;
+        }
+        obtainable.extend(fusible);
+    }
+
+    obtainable
+}
+
+/// The issues are sorted by kind and ids.
+pub fn lint_dataset(data: &GameData) -> Vec<LintIssue> {
+    let monsters = &data.monsters;
+    let families = &data.families;
+    let mut issues: Vec<LintIssue> = vec![];
+
+    for (&id, monster) in monsters.iter() {
+        if !data.ranks.contains_key(&monster.rank) {
+            issues.push(LintIssue::UnknownRank {
+                monster: id,
+                rank: monster.rank,
+            });
+        }
+        if !families.contains_key(&monster.family) {
+            issues.push(LintIssue::UnknownFamily {
+                monster: id,
+                family: monster.family,
+            });
+        }
+
+        let mut recipes: HashSet<Vec<(Option<MonsterId>, Option<FamilyId>)>> = HashSet::new();
+        for (recipe, parents) in monster.parents.iter().enumerate() {
+            if !recipes.insert(recipe_key(parents)) {
+                issues.push(LintIssue::DuplicateRecipe {
+                    monster: id,
+                    recipe,
+                });
+            }
+            if parents.iter().any(|p| p.monster == Some(id)) {
+                issues.push(LintIssue::SelfReferencingRecipe {
+                    monster: id,
+                    recipe,
+                });
+            }
+            if is_large_recipe(parents) && monster.details.size == Some(BodySize::Normal) {
+                issues.push(LintIssue::FourParentNormalSize {
+                    monster: id,
+                    recipe,
+                });
+            }
+            for parent in parents.iter() {
+                match parent.monster {
+                    Some(parent) if !monsters.contains_key(&parent) => {
+                        issues.push(LintIssue::UnknownParentMonster {
+                            monster: id,
+                            parent,
+                        })
+                    }
+                    _ => (),
+                }
+                match parent.family {
+                    Some(family) if !families.contains_key(&family) => {
+                        issues.push(LintIssue::UnknownFamily {
+                            monster: id,
+                            family,
+                        })
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        for (&area, habitat) in monster.habitats.iter() {
+            if !data.areas.contains_key(&area) {
+                issues.push(LintIssue::UnknownArea { monster: id, area });
+            }
+            for &season in habitat.seasons.keys() {
+                if !data.seasons.contains_key(&season) {
+                    issues.push(LintIssue::UnknownSeason {
+                        monster: id,
+                        area,
+                        season,
+                    });
+                }
+            }
+        }
+    }
+
+    // Names are searched in every locale, so each of them has to be unique
+    for locale in Locale::ALL {
+        let mut names: HashMap<&str, Vec<MonsterId>> = HashMap::new();
+        for (&id, monster) in monsters.iter() {
+            names.entry(monster.name.get(locale)).or_default().push(id);
+        }
+        for (name, mut ids) in names.into_iter() {
+            if ids.len() > 1 {
+                ids.sort();
+                issues.push(LintIssue::DuplicateName {
+                    name: name.to_string(),
+                    monsters: ids,
+                });
+            }
+        }
+    }
+
+    let obtainable = find_obtainable_monsters(monsters);
+    issues.extend(
+        monsters
+            .keys()
+            .filter(|id| !obtainable.contains(id))
+            .map(|&monster| LintIssue::Unreachable { monster }),
+    );
+
+    issues.sort();
+    issues.dedup();
+    issues
+}
+
+#[test]
+fn test_lint_embedded_data() {
+    let issues = lint_dataset(GameData::embedded().unwrap());
+    let errors = issues
+        .iter()
+        .filter(|issue| issue.severity() == Severity::Error)
+        .collect::<Vec<&LintIssue>>();
+    assert_eq!(errors, Vec::<&LintIssue>::new());
+}
+
+#[test]
+fn test_lint_dataset() {
+    use super::data::{Habitat, LocalizedName, MonsterDetails, SeasonHabitat};
+
+    let parent = |monster: Option<usize>, family: Option<usize>| Parent {
+        monster: monster.map(MonsterId),
+        family: family.map(FamilyId),
+    };
+    let monsters: HashMap<MonsterId, Monster> = HashMap::from([
+        (
+            MonsterId(0),
+            Monster {
+                name: LocalizedName {
+                    ja: "a".to_string(),
+                    en: Some("b".to_string()),
+                },
+                rank: RankId(0),
+                family: FamilyId(0),
+                parents: vec![],
+                habitats: HashMap::from([(
+                    AreaId(0),
+                    Habitat {
+                        seasons: HashMap::from([
+                            (SeasonId(0), SeasonHabitat::new(&[true, false]).unwrap()),
+                            (SeasonId(9), SeasonHabitat::new(&[true, false]).unwrap()),
+                        ]),
+                    },
+                )]),
+                details: MonsterDetails::default(),
+            },
+        ),
+        (
+            MonsterId(1),
+            Monster {
+                name: "b".into(),
+                rank: RankId(9),
+                family: FamilyId(0),
+                parents: vec![
+                    vec![parent(Some(0), None), parent(None, Some(0))],
+                    vec![parent(None, Some(0)), parent(Some(0), None)],
+                    vec![parent(Some(1), None), parent(Some(5), None)],
+                    vec![parent(Some(0), None); 4],
+                ],
+                habitats: HashMap::from([(
+                    AreaId(9),
+                    Habitat {
+                        seasons: HashMap::new(),
+                    },
+                )]),
+                details: MonsterDetails {
+                    size: Some(BodySize::Normal),
+                    ..MonsterDetails::default()
+                },
+            },
+        ),
+        (
+            MonsterId(2),
+            Monster {
+                name: "b".into(),
+                rank: RankId(0),
+                family: FamilyId(9),
+                parents: vec![vec![parent(Some(2), None), parent(None, Some(8))]],
+                habitats: HashMap::new(),
+                details: MonsterDetails::default(),
+            },
+        ),
+    ]);
+    let actual = lint_dataset(&GameData {
+        monsters,
+        families: HashMap::from([(FamilyId(0), "x".into())]),
+        ranks: HashMap::from([(RankId(0), "x".into())]),
+        areas: HashMap::from([(AreaId(0), "x".into())]),
+        seasons: HashMap::from([(SeasonId(0), "x".into())]),
+        ..GameData::default()
+    });
+    let expected = vec![
+        LintIssue::UnknownParentMonster {
+            monster: MonsterId(1),
+            parent: MonsterId(5),
+        },
+        LintIssue::UnknownFamily {
+            monster: MonsterId(2),
+            family: FamilyId(8),
+        },
+        LintIssue::UnknownFamily {
+            monster: MonsterId(2),
+            family: FamilyId(9),
+        },
+        LintIssue::UnknownRank {
+            monster: MonsterId(1),
+            rank: RankId(9),
+        },
+        LintIssue::UnknownArea {
+            monster: MonsterId(1),
+            area: AreaId(9),
+        },
+        LintIssue::UnknownSeason {
+            monster: MonsterId(0),
+            area: AreaId(0),
+            season: SeasonId(9),
+        },
+        LintIssue::DuplicateName {
+            name: "b".to_string(),
+            monsters: vec![MonsterId(0), MonsterId(1), MonsterId(2)],
+        },
+        LintIssue::DuplicateName {
+            name: "b".to_string(),
+            monsters: vec![MonsterId(1), MonsterId(2)],
+        },
+        LintIssue::DuplicateRecipe {
+            monster: MonsterId(1),
+            recipe: 1,
+        },
+        LintIssue::SelfReferencingRecipe {
+            monster: MonsterId(1),
+            recipe: 2,
+        },
+        LintIssue::SelfReferencingRecipe {
+            monster: MonsterId(2),
+            recipe: 0,
+        },
+        LintIssue::FourParentNormalSize {
+            monster: MonsterId(1),
+            recipe: 3,
+        },
+        LintIssue::Unreachable {
+            monster: MonsterId(2),
+        },
+    ];
+    assert_eq!(actual, expected);
+}
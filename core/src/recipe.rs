@@ -0,0 +1,1243 @@
+use super::data::{AreaId, BodySize, FamilyId, Monster, MonsterId, Parent, RankId, SeasonId};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::iter::Iterator;
+use std::rc::Rc;
+
+#[derive(PartialEq, Clone, Debug)]
+pub struct MonsterSpec {
+    pub family: FamilyId,
+    pub rank: RankId,
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub struct MonsterInfo {
+    pub spec: Option<MonsterSpec>,
+    pub monster_id: Option<MonsterId>,
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub struct MonsterNode {
+    pub data: MonsterInfo,
+    pub children: Vec<Rc<RefCell<MonsterNode>>>,
+}
+
+type MonsterBranch = Vec<Rc<RefCell<MonsterNode>>>;
+
+struct MutableMonsterBranchIterator {
+    root: Rc<RefCell<MonsterNode>>,
+    indices: Vec<usize>,
+    stop: bool,
+}
+
+impl MutableMonsterBranchIterator {
+    fn new(root: Rc<RefCell<MonsterNode>>) -> Self {
+        Self {
+            root,
+            indices: vec![],
+            stop: false,
+        }
+    }
+
+    fn get_branch(&self) -> MonsterBranch {
+        let mut branch = vec![self.root.clone()];
+        let mut cursor = self.root.clone();
+        for &index in self.indices.iter() {
+            branch.push(cursor.borrow().children[index].clone());
+            cursor = branch.last().unwrap().clone();
+        }
+
+        branch
+    }
+}
+
+impl Iterator for MutableMonsterBranchIterator {
+    type Item = Vec<Rc<RefCell<MonsterNode>>>;
+
+    #[allow(clippy::len_zero)]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.stop {
+            return None;
+        }
+
+        let branch = self.get_branch();
+
+        // Next index
+        if branch.last().unwrap().borrow().children.len() > 0 {
+            self.indices.push(0);
+            return Some(branch);
+        }
+
+        while let Some(index) = self.indices.pop() {
+            let cursor = self.indices.len();
+            if branch[cursor].borrow().children.len() > index + 1 {
+                self.indices.push(index + 1);
+                return Some(branch);
+            }
+        }
+        self.stop = true;
+        Some(branch)
+    }
+}
+
+pub struct MonsterTreeBuilder {
+    lut: HashMap<MonsterId, Monster>,
+    prefer_standard: bool,
+    max_size: Option<BodySize>,
+    rank_range: [Option<RankId>; 2],
+    stop_at_scoutable: bool,
+    avoid_large_recipes: bool,
+}
+
+impl MonsterTreeBuilder {
+    pub fn new(monster_lut: HashMap<MonsterId, Monster>) -> Self {
+        Self {
+            lut: monster_lut,
+            prefer_standard: false,
+            max_size: None,
+            rank_range: [None, None],
+            stop_at_scoutable: false,
+            avoid_large_recipes: false,
+        }
+    }
+
+    /// A monster whose recipes are all skipped becomes a material.
+    pub fn limit_size(&mut self, max_size: Option<BodySize>) {
+        self.max_size = max_size;
+    }
+
+    pub fn prefer_standard_fusion(&mut self, prefer_standard: bool) {
+        self.prefer_standard = prefer_standard;
+    }
+
+    /// `rank_range` is given as the highest and the lowest rank. Family parents are not limited.
+    /// Bounds given the other way around are swapped.
+    pub fn limit_rank(&mut self, rank_range: [Option<RankId>; 2]) {
+        self.rank_range = match rank_range {
+            [Some(highest), Some(lowest)] if highest > lowest => [Some(lowest), Some(highest)],
+            _ => rank_range,
+        };
+    }
+
+    pub fn stop_at_scoutable(&mut self, stop_at_scoutable: bool) {
+        self.stop_at_scoutable = stop_at_scoutable;
+    }
+
+    pub fn avoid_large_recipes(&mut self, avoid_large_recipes: bool) {
+        self.avoid_large_recipes = avoid_large_recipes;
+    }
+
+    #[allow(clippy::manual_map)]
+    fn get_parents_info(&self, monster_id: MonsterId) -> Vec<Vec<MonsterInfo>> {
+        let monster = &self.lut[&monster_id];
+        monster
+            .parents
+            .iter()
+            .filter(|ps| !(self.avoid_large_recipes && is_large_recipe(ps)))
+            .map(|ps| {
+                ps.iter()
+                    .map(|p| MonsterInfo {
+                        spec: match p.family {
+                            Some(id) => Some(MonsterSpec {
+                                family: id,
+                                rank: monster.rank,
+                            }),
+                            None => None,
+                        },
+                        monster_id: p.monster,
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn fits_size(&self, monster_id: MonsterId) -> bool {
+        // A monster that may be giant only fits when giants do
+        match self.max_size {
+            Some(max_size) => {
+                body_size(&self.lut[&monster_id]).unwrap_or(BodySize::Giant) <= max_size
+            }
+            None => true,
+        }
+    }
+
+    fn fits_rank(&self, monster_id: MonsterId) -> bool {
+        match self.rank_range {
+            [None, None] => true,
+            _ => validate_monster_rank(&self.lut[&monster_id], &self.rank_range),
+        }
+    }
+
+    fn is_cyclic_parent(&self, parent_id: MonsterId, branch: &MonsterBranch) -> bool {
+        let monster_ids = branch
+            .iter()
+            .map(|n| n.borrow().data.monster_id.unwrap())
+            .collect::<HashSet<MonsterId>>();
+        monster_ids.contains(&parent_id)
+    }
+
+    fn select_parents(
+        &self,
+        parents_list: Vec<Vec<MonsterInfo>>,
+        branch: &MonsterBranch,
+    ) -> Option<Vec<MonsterInfo>> {
+        // Exclude cyclic branch and parents too large or out of the rank range
+        let filtered_parents: Vec<Vec<MonsterInfo>> = parents_list
+            .into_iter()
+            .filter(|ps| {
+                ps.iter().fold(true, |acc, p| match p.monster_id {
+                    Some(id) if self.is_cyclic_parent(id, branch) => false,
+                    Some(id) if !self.fits_size(id) => false,
+                    Some(id) if !self.fits_rank(id) => false,
+                    _ => acc,
+                })
+            })
+            .collect();
+
+        let count_standard_parents = |parents: &Vec<MonsterInfo>| {
+            parents
+                .iter()
+                .filter(|info| info.spec.is_some())
+                .collect::<Vec<&MonsterInfo>>()
+                .len()
+        };
+
+        match self.prefer_standard {
+            true => filtered_parents
+                .into_iter()
+                .max_by_key(count_standard_parents),
+            false => filtered_parents
+                .into_iter()
+                .min_by_key(count_standard_parents),
+        }
+    }
+
+    /// The parents of the last node of `branch`, or none when it is a material.
+    fn get_child_nodes(&self, branch: &MonsterBranch) -> Vec<Rc<RefCell<MonsterNode>>> {
+        let monster_id = match branch.last().unwrap().borrow().data.monster_id {
+            Some(monster_id) => monster_id,
+            None => return vec![],
+        };
+        // Scoutable parents are materials, but the target is always fused
+        if self.stop_at_scoutable && branch.len() > 1 && is_scoutable(&self.lut[&monster_id]) {
+            return vec![];
+        }
+
+        // None when the monster has no recipe, or every recipe is cyclic or filtered out
+        let parents_list = self.get_parents_info(monster_id);
+        let parents = self.select_parents(parents_list, branch);
+        match parents {
+            Some(ps) => ps
+                .iter()
+                .map(|data| {
+                    Rc::new(RefCell::new(MonsterNode {
+                        data: data.clone(),
+                        children: vec![],
+                    }))
+                })
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    pub fn build(&self, monster_id: MonsterId) -> MonsterNode {
+        let root = Rc::new(RefCell::new(MonsterNode {
+            data: MonsterInfo {
+                monster_id: Some(monster_id),
+                spec: None,
+            },
+            children: vec![],
+        }));
+
+        // Expand the tree one level at a time, so that each node is visited once
+        let mut depth = 1;
+        loop {
+            let branches: Vec<MonsterBranch> = MutableMonsterBranchIterator::new(root.clone())
+                .filter(|b| b.len() == depth)
+                .collect();
+            if branches.is_empty() {
+                break;
+            }
+            for branch in branches.iter() {
+                branch.last().unwrap().try_borrow_mut().unwrap().children =
+                    self.get_child_nodes(branch);
+            }
+            depth += 1;
+        }
+
+        Rc::try_unwrap(root).unwrap().into_inner()
+    }
+}
+
+#[derive(Clone, Hash, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Material {
+    Monster(MonsterId),
+    /// `rank` is set when all the families are collapsed from a single fusion.
+    Family {
+        families: Vec<FamilyId>,
+        rank: Option<RankId>,
+    },
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct MaterialSummary {
+    pub material: Material,
+    pub count: usize,
+}
+
+fn try_get_material(
+    monster_lut: &HashMap<MonsterId, Monster>,
+    node: &MonsterNode,
+) -> Option<Material> {
+    match node.data.monster_id {
+        Some(id) => {
+            let mut standard_children = node
+                .children
+                .iter()
+                .filter_map(|c| c.borrow().data.spec.clone())
+                .map(|s| s.family)
+                .collect::<Vec<FamilyId>>();
+            standard_children.sort();
+
+            match (standard_children.len(), node.children.len()) {
+                (_, 0) => Some(Material::Monster(id)),
+                // Parents consisting only of families are shown as a single line
+                (sc, ac) if sc == ac => Some(Material::Family {
+                    families: standard_children,
+                    rank: Some(monster_lut[&id].rank),
+                }),
+                _ => None,
+            }
+        }
+        None => Some(Material::Family {
+            families: node.data.spec.iter().map(|spec| spec.family).collect(),
+            rank: None,
+        }),
+    }
+}
+
+/// The result is sorted by material.
+pub fn summarize_materials(
+    monster_lut: &HashMap<MonsterId, Monster>,
+    root: Rc<RefCell<MonsterNode>>,
+) -> Vec<MaterialSummary> {
+    let mut materials: HashMap<Material, usize> = HashMap::new();
+    let mut stack = vec![root];
+
+    while let Some(node) = stack.pop() {
+        match try_get_material(monster_lut, &node.borrow()) {
+            Some(material) => *materials.entry(material).or_insert(0) += 1,
+            None => stack.extend(node.borrow().children.clone()),
+        }
+    }
+
+    let mut summaries = materials
+        .into_iter()
+        .map(|(material, count)| MaterialSummary { material, count })
+        .collect::<Vec<MaterialSummary>>();
+    summaries.sort_by(|a, b| a.material.cmp(&b.material));
+    summaries
+}
+
+fn validate_monster_rank(monster: &Monster, rank_range: &[Option<RankId>; 2]) -> bool {
+    match rank_range {
+        [Some(min), _] if min > &monster.rank => false,
+        [_, Some(max)] if max < &monster.rank => false,
+        _ => true,
+    }
+}
+
+/// Only large and giant monsters are fused from four parents.
+pub fn is_large_recipe(parents: &[Parent]) -> bool {
+    parents.len() == 4
+}
+
+/// When `monster.json` does not record the size, a monster with a four-parent recipe
+/// may be large or giant, so `None` is returned, and any other is normal.
+pub fn body_size(monster: &Monster) -> Option<BodySize> {
+    match monster.details.size {
+        Some(size) => Some(size),
+        None if monster.parents.iter().any(|ps| is_large_recipe(ps)) => None,
+        None => Some(BodySize::Normal),
+    }
+}
+
+/// Sorted by id. Recipes that accept any monster of the family of `monster_id` are not counted.
+pub fn used_in(monster_lut: &HashMap<MonsterId, Monster>, monster_id: MonsterId) -> Vec<MonsterId> {
+    let mut children = monster_lut
+        .iter()
+        .filter(|(_, m)| {
+            m.parents
+                .iter()
+                .flatten()
+                .any(|p| p.monster == Some(monster_id))
+        })
+        .map(|(&id, _)| id)
+        .collect::<Vec<MonsterId>>();
+    children.sort();
+    children
+}
+
+/// Monsters with a recipe of exactly `first` and `second`, in either order, sorted by rank.
+/// Standard fusions are not listed, since which monster of a family they produce depends on
+/// the ranks of the parents, which the data does not record.
+pub fn fusion_results(
+    monster_lut: &HashMap<MonsterId, Monster>,
+    first: MonsterId,
+    second: MonsterId,
+) -> Vec<MonsterId> {
+    let is_special = |ps: &Vec<Parent>| match &ps[..] {
+        [p, q] => {
+            (p.monster, q.monster) == (Some(first), Some(second))
+                || (p.monster, q.monster) == (Some(second), Some(first))
+        }
+        _ => false,
+    };
+
+    let mut special = monster_lut
+        .iter()
+        .filter(|(_, monster)| monster.parents.iter().any(is_special))
+        .map(|(&id, monster)| (monster.rank, id))
+        .collect::<Vec<(RankId, MonsterId)>>();
+    special.sort();
+    special.into_iter().map(|(_, id)| id).collect()
+}
+
+pub fn is_scoutable(monster: &Monster) -> bool {
+    monster.habitats.values().any(|h| !h.seasons.is_empty())
+}
+
+/// The habitat flags are ignored.
+pub fn is_scoutable_in(monster: &Monster, area: AreaId, season: SeasonId) -> bool {
+    monster
+        .habitats
+        .get(&area)
+        .and_then(|h| h.in_season(season))
+        .is_some()
+}
+
+#[test]
+fn test_mutable_monster_branch_iterator() {
+    /*
+     * 6 +- 4 +- 0
+     *   |    +- 1
+     *   |
+     *   +- 5 +- 2
+     *        +- 3
+     */
+    let leaves1 = [
+        MonsterNode {
+            data: MonsterInfo {
+                spec: None,
+                monster_id: Some(MonsterId(0)),
+            },
+            children: vec![],
+        },
+        MonsterNode {
+            data: MonsterInfo {
+                spec: None,
+                monster_id: Some(MonsterId(1)),
+            },
+            children: vec![],
+        },
+    ];
+    let leaves2 = [
+        MonsterNode {
+            data: MonsterInfo {
+                spec: None,
+                monster_id: Some(MonsterId(2)),
+            },
+            children: vec![],
+        },
+        MonsterNode {
+            data: MonsterInfo {
+                spec: None,
+                monster_id: Some(MonsterId(3)),
+            },
+            children: vec![],
+        },
+    ];
+    let middle_nodes = [
+        MonsterNode {
+            data: MonsterInfo {
+                spec: None,
+                monster_id: Some(MonsterId(4)),
+            },
+            children: leaves1
+                .iter()
+                .map(|n| Rc::new(RefCell::new(n.clone())))
+                .collect(),
+        },
+        MonsterNode {
+            data: MonsterInfo {
+                spec: None,
+                monster_id: Some(MonsterId(5)),
+            },
+            children: leaves2
+                .iter()
+                .map(|n| Rc::new(RefCell::new(n.clone())))
+                .collect(),
+        },
+    ];
+    let root = MonsterNode {
+        data: MonsterInfo {
+            spec: None,
+            monster_id: Some(MonsterId(6)),
+        },
+        children: middle_nodes
+            .iter()
+            .map(|n| Rc::new(RefCell::new(n.clone())))
+            .collect(),
+    };
+
+    let mut itr = MutableMonsterBranchIterator::new(Rc::new(RefCell::new(root)));
+
+    let expected_ids: Vec<Vec<usize>> = vec![
+        vec![6],
+        vec![6, 4],
+        vec![6, 4, 0],
+        vec![6, 4, 1],
+        vec![6, 5],
+        vec![6, 5, 2],
+        vec![6, 5, 3],
+    ];
+
+    for expected in expected_ids {
+        let branch = itr.next();
+        assert!(branch.is_some());
+
+        let actual = branch
+            .unwrap()
+            .iter()
+            .map(|n| n.borrow().data.monster_id.unwrap().0)
+            .collect::<Vec<usize>>();
+        assert_eq!(actual, expected);
+    }
+    assert!(itr.next().is_none());
+}
+
+#[test]
+fn test_get_parents_info() {
+    use super::data::{MonsterDetails, Parent};
+
+    let lut: HashMap<MonsterId, Monster> = HashMap::from([
+        (
+            MonsterId(0),
+            Monster {
+                name: "a".into(),
+                rank: RankId(0),
+                family: FamilyId(0),
+                parents: vec![],
+                habitats: HashMap::new(),
+                details: MonsterDetails::default(),
+            },
+        ),
+        (
+            MonsterId(1),
+            Monster {
+                name: "a".into(),
+                rank: RankId(0),
+                family: FamilyId(1),
+                parents: vec![
+                    vec![
+                        Parent {
+                            monster: None,
+                            family: Some(FamilyId(2)),
+                        },
+                        Parent {
+                            monster: None,
+                            family: Some(FamilyId(3)),
+                        },
+                    ],
+                    vec![
+                        Parent {
+                            monster: Some(MonsterId(4)),
+                            family: None,
+                        },
+                        Parent {
+                            monster: Some(MonsterId(5)),
+                            family: None,
+                        },
+                    ],
+                ],
+                habitats: HashMap::new(),
+                details: MonsterDetails::default(),
+            },
+        ),
+    ]);
+
+    let builder = MonsterTreeBuilder::new(lut);
+    let actual1 = builder.get_parents_info(MonsterId(0));
+    assert_eq!(actual1.len(), 0);
+
+    let actual2 = builder.get_parents_info(MonsterId(1));
+    assert_eq!(actual2.len(), 2);
+    assert_eq!(actual2[0].len(), 2);
+    assert_eq!(
+        actual2[0][0],
+        MonsterInfo {
+            spec: Some(MonsterSpec {
+                rank: RankId(0),
+                family: FamilyId(2)
+            }),
+            monster_id: None
+        }
+    );
+    assert_eq!(
+        actual2[0][1],
+        MonsterInfo {
+            spec: Some(MonsterSpec {
+                rank: RankId(0),
+                family: FamilyId(3)
+            }),
+            monster_id: None
+        }
+    );
+}
+
+#[test]
+fn test_select_parents() {
+    let mut builder = MonsterTreeBuilder::new(HashMap::new());
+
+    let info1 = vec![
+        MonsterInfo {
+            spec: Some(MonsterSpec {
+                family: FamilyId(0),
+                rank: RankId(1),
+            }),
+            monster_id: None,
+        },
+        MonsterInfo {
+            spec: Some(MonsterSpec {
+                family: FamilyId(2),
+                rank: RankId(1),
+            }),
+            monster_id: None,
+        },
+    ];
+    let info2 = vec![
+        MonsterInfo {
+            spec: Some(MonsterSpec {
+                family: FamilyId(0),
+                rank: RankId(1),
+            }),
+            monster_id: None,
+        },
+        MonsterInfo {
+            spec: None,
+            monster_id: Some(MonsterId(3)),
+        },
+    ];
+    let info3 = vec![
+        MonsterInfo {
+            spec: None,
+            monster_id: Some(MonsterId(3)),
+        },
+        MonsterInfo {
+            spec: None,
+            monster_id: Some(MonsterId(4)),
+        },
+    ];
+
+    let parents1: Vec<Vec<MonsterInfo>> = vec![];
+    let parents2 = vec![info1.clone(), info2.clone(), info3.clone()];
+    let branch = vec![Rc::new(RefCell::new(MonsterNode {
+        data: MonsterInfo {
+            spec: None,
+            monster_id: Some(MonsterId(6)),
+        },
+        children: vec![],
+    }))];
+
+    let actual1f = builder.select_parents(parents1.clone(), &branch);
+    assert_eq!(actual1f, None);
+    let actual2f = builder.select_parents(parents2.clone(), &branch);
+    assert!(actual2f.is_some());
+    assert_eq!(actual2f.unwrap().clone(), info3.clone());
+
+    builder.prefer_standard_fusion(true);
+    let actual1t = builder.select_parents(parents1.clone(), &branch);
+    assert_eq!(actual1t, None);
+    let actual2t = builder.select_parents(parents2.clone(), &branch);
+    assert!(actual2t.is_some());
+    assert_eq!(actual2t.unwrap().clone(), info1.clone());
+}
+
+#[test]
+#[allow(clippy::bool_assert_comparison)]
+fn test_validate_monster_rank() {
+    use super::data::MonsterDetails;
+
+    let monster = Monster {
+        name: "a".into(),
+        rank: RankId(3),
+        family: FamilyId(0),
+        parents: vec![],
+        habitats: HashMap::new(),
+        details: MonsterDetails::default(),
+    };
+    assert_eq!(
+        validate_monster_rank(&monster, &[Some(RankId(0)), Some(RankId(2))]),
+        false
+    );
+    assert_eq!(
+        validate_monster_rank(&monster, &[Some(RankId(0)), Some(RankId(3))]),
+        true
+    );
+    assert_eq!(
+        validate_monster_rank(&monster, &[Some(RankId(0)), Some(RankId(7))]),
+        true
+    );
+    assert_eq!(
+        validate_monster_rank(&monster, &[Some(RankId(3)), Some(RankId(7))]),
+        true
+    );
+    assert_eq!(
+        validate_monster_rank(&monster, &[Some(RankId(4)), Some(RankId(7))]),
+        false
+    );
+    assert_eq!(
+        validate_monster_rank(&monster, &[None, Some(RankId(2))]),
+        false
+    );
+    assert_eq!(
+        validate_monster_rank(&monster, &[None, Some(RankId(3))]),
+        true
+    );
+    assert_eq!(
+        validate_monster_rank(&monster, &[None, Some(RankId(4))]),
+        true
+    );
+    assert_eq!(
+        validate_monster_rank(&monster, &[Some(RankId(2)), None]),
+        true
+    );
+    assert_eq!(
+        validate_monster_rank(&monster, &[Some(RankId(3)), None]),
+        true
+    );
+    assert_eq!(
+        validate_monster_rank(&monster, &[Some(RankId(4)), None]),
+        false
+    );
+    assert_eq!(validate_monster_rank(&monster, &[None, None]), true);
+}
+
+#[test]
+#[allow(clippy::bool_assert_comparison)]
+fn test_is_scoutable() {
+    let monster1 = Monster {
+        name: "a".into(),
+        rank: RankId(0),
+        family: FamilyId(0),
+        parents: vec![],
+        habitats: HashMap::new(),
+        details: MonsterDetails::default(),
+    };
+    assert_eq!(is_scoutable(&monster1), false);
+
+    use super::data::{Habitat, MonsterDetails, SeasonHabitat};
+    let monster2 = Monster {
+        name: "a".into(),
+        rank: RankId(0),
+        family: FamilyId(0),
+        parents: vec![],
+        habitats: vec![(
+            AreaId(0),
+            Habitat {
+                seasons: vec![(SeasonId(0), SeasonHabitat::new(&[true, false]).unwrap())]
+                    .into_iter()
+                    .collect(),
+            },
+        )]
+        .into_iter()
+        .collect(),
+        details: MonsterDetails::default(),
+    };
+    assert_eq!(is_scoutable(&monster2), true);
+    assert!(is_scoutable_in(&monster2, AreaId(0), SeasonId(0)));
+    assert!(!is_scoutable_in(&monster2, AreaId(0), SeasonId(1)));
+    assert!(!is_scoutable_in(&monster2, AreaId(1), SeasonId(0)));
+    assert!(!is_scoutable_in(&monster1, AreaId(0), SeasonId(0)));
+}
+
+#[cfg(test)]
+fn test_monster(rank: usize, family: usize, parents: Vec<Vec<Parent>>) -> Monster {
+    Monster {
+        name: "a".into(),
+        rank: RankId(rank),
+        family: FamilyId(family),
+        parents,
+        habitats: HashMap::new(),
+        details: super::data::MonsterDetails::default(),
+    }
+}
+
+#[cfg(test)]
+fn monster_parent(id: usize) -> Parent {
+    Parent {
+        monster: Some(MonsterId(id)),
+        family: None,
+    }
+}
+
+#[cfg(test)]
+fn family_parent(id: usize) -> Parent {
+    Parent {
+        monster: None,
+        family: Some(FamilyId(id)),
+    }
+}
+
+#[test]
+fn test_used_in() {
+    let family = family_parent(0);
+    let lut = HashMap::from([
+        (MonsterId(0), test_monster(0, 0, vec![])),
+        (
+            MonsterId(1),
+            test_monster(0, 0, vec![vec![family.clone(), family.clone()]]),
+        ),
+        (
+            MonsterId(2),
+            test_monster(0, 0, vec![vec![monster_parent(0), family.clone()]]),
+        ),
+        (
+            MonsterId(3),
+            test_monster(
+                0,
+                0,
+                vec![
+                    vec![monster_parent(1), monster_parent(2)],
+                    vec![monster_parent(1), monster_parent(0)],
+                ],
+            ),
+        ),
+    ]);
+
+    assert_eq!(
+        used_in(&lut, MonsterId(0)),
+        vec![MonsterId(2), MonsterId(3)]
+    );
+    assert_eq!(used_in(&lut, MonsterId(2)), vec![MonsterId(3)]);
+    assert_eq!(used_in(&lut, MonsterId(3)), vec![]);
+}
+
+#[test]
+fn test_fusion_results() {
+    let lut = HashMap::from([
+        (MonsterId(0), test_monster(5, 0, vec![])),
+        (MonsterId(1), test_monster(4, 1, vec![])),
+        (
+            MonsterId(2),
+            test_monster(5, 0, vec![vec![family_parent(0), family_parent(1)]]),
+        ),
+        (
+            MonsterId(3),
+            test_monster(4, 0, vec![vec![family_parent(0), family_parent(1)]]),
+        ),
+        (
+            MonsterId(4),
+            test_monster(3, 0, vec![vec![family_parent(0), family_parent(1)]]),
+        ),
+        (
+            MonsterId(5),
+            test_monster(4, 1, vec![vec![family_parent(1), family_parent(0)]]),
+        ),
+        (
+            MonsterId(6),
+            test_monster(
+                2,
+                1,
+                vec![
+                    vec![family_parent(1), family_parent(1)],
+                    vec![monster_parent(1), monster_parent(0)],
+                ],
+            ),
+        ),
+        (
+            MonsterId(7),
+            test_monster(1, 1, vec![vec![monster_parent(0), monster_parent(1)]]),
+        ),
+    ]);
+
+    // The recipes of families are not taken
+    assert_eq!(
+        fusion_results(&lut, MonsterId(0), MonsterId(1)),
+        vec![MonsterId(7), MonsterId(6)]
+    );
+    assert_eq!(
+        fusion_results(&lut, MonsterId(1), MonsterId(0)),
+        vec![MonsterId(7), MonsterId(6)]
+    );
+    assert_eq!(fusion_results(&lut, MonsterId(0), MonsterId(0)), vec![]);
+    assert_eq!(fusion_results(&lut, MonsterId(0), MonsterId(9)), vec![]);
+}
+
+#[test]
+fn test_build_recipe() {
+    use super::data::{MonsterDetails, Parent};
+    /*
+     * 0 +- 1 +- 2 +- 3
+     *   |    |    +- X
+     *   |    |
+     *   |    +- 3 +- 2
+     *   |         +- X
+     *   |
+     *   +- 2 +- 3
+     *        +- X
+     */
+    let lut: HashMap<MonsterId, Monster> = HashMap::from([
+        (
+            MonsterId(0),
+            Monster {
+                name: "a".into(),
+                rank: RankId(0),
+                family: FamilyId(1),
+                parents: vec![vec![
+                    Parent {
+                        monster: Some(MonsterId(1)),
+                        family: None,
+                    },
+                    Parent {
+                        monster: Some(MonsterId(2)),
+                        family: None,
+                    },
+                ]],
+                habitats: HashMap::new(),
+                details: MonsterDetails::default(),
+            },
+        ),
+        (
+            MonsterId(1),
+            Monster {
+                name: "a".into(),
+                rank: RankId(0),
+                family: FamilyId(0),
+                parents: vec![vec![
+                    Parent {
+                        monster: Some(MonsterId(2)),
+                        family: None,
+                    },
+                    Parent {
+                        monster: Some(MonsterId(3)),
+                        family: None,
+                    },
+                ]],
+                habitats: HashMap::new(),
+                details: MonsterDetails::default(),
+            },
+        ),
+        (
+            MonsterId(2),
+            Monster {
+                name: "a".into(),
+                rank: RankId(0),
+                family: FamilyId(0),
+                parents: vec![vec![
+                    Parent {
+                        monster: Some(MonsterId(3)),
+                        family: None,
+                    },
+                    Parent {
+                        monster: None,
+                        family: Some(FamilyId(0)),
+                    },
+                ]],
+                habitats: HashMap::new(),
+                details: MonsterDetails::default(),
+            },
+        ),
+        (
+            MonsterId(3),
+            Monster {
+                name: "a".into(),
+                rank: RankId(0),
+                family: FamilyId(0),
+                parents: vec![vec![
+                    Parent {
+                        monster: Some(MonsterId(2)),
+                        family: None,
+                    },
+                    Parent {
+                        monster: None,
+                        family: Some(FamilyId(0)),
+                    },
+                ]],
+                habitats: HashMap::new(),
+                details: MonsterDetails::default(),
+            },
+        ),
+    ]);
+
+    let builder = MonsterTreeBuilder::new(lut);
+    let actual = builder.build(MonsterId(0));
+
+    let actual_ids = MutableMonsterBranchIterator::new(Rc::new(RefCell::new(actual)))
+        .map(|b| {
+            b.iter()
+                .map(|n| n.borrow().data.monster_id.map(|id| id.0))
+                .collect::<Vec<Option<usize>>>()
+        })
+        .collect::<Vec<Vec<Option<usize>>>>();
+    let expected_ids = vec![
+        vec![Some(0)],
+        vec![Some(0), Some(1)],
+        vec![Some(0), Some(1), Some(2)],
+        vec![Some(0), Some(1), Some(2), Some(3)],
+        vec![Some(0), Some(1), Some(2), None],
+        vec![Some(0), Some(1), Some(3)],
+        vec![Some(0), Some(1), Some(3), Some(2)],
+        vec![Some(0), Some(1), Some(3), None],
+        vec![Some(0), Some(2)],
+        vec![Some(0), Some(2), Some(3)],
+        vec![Some(0), Some(2), None],
+    ];
+    assert_eq!(actual_ids, expected_ids);
+}
+
+#[test]
+fn test_body_size() {
+    let mut monster = test_monster(0, 0, vec![vec![monster_parent(1), monster_parent(2)]]);
+    assert!(!is_large_recipe(&monster.parents[0]));
+    assert_eq!(body_size(&monster), Some(BodySize::Normal));
+
+    monster.parents.push(vec![
+        monster_parent(1),
+        monster_parent(2),
+        monster_parent(3),
+        monster_parent(4),
+    ]);
+    assert!(is_large_recipe(&monster.parents[1]));
+    assert_eq!(body_size(&monster), None);
+
+    // The recorded size wins over the inferred one
+    monster.details.size = Some(BodySize::Large);
+    assert_eq!(body_size(&monster), Some(BodySize::Large));
+}
+
+#[test]
+fn test_limit_size() {
+    use super::data::MonsterDetails;
+
+    let monster = |parents: Vec<Vec<Parent>>, size: Option<BodySize>| Monster {
+        details: MonsterDetails {
+            size,
+            ..MonsterDetails::default()
+        },
+        ..test_monster(0, 0, parents)
+    };
+    /*
+     * 0 +- 1 (giant)
+     *   +- 2
+     * 0 +- 2
+     *   +- 3
+     * 3 +- 1 (giant)
+     *   +- 2
+     */
+    let lut = HashMap::from([
+        (
+            MonsterId(0),
+            monster(
+                vec![
+                    vec![monster_parent(1), monster_parent(2)],
+                    vec![monster_parent(2), monster_parent(3)],
+                ],
+                None,
+            ),
+        ),
+        (MonsterId(1), monster(vec![], Some(BodySize::Giant))),
+        (MonsterId(2), monster(vec![], None)),
+        (
+            MonsterId(3),
+            monster(vec![vec![monster_parent(1), monster_parent(2)]], None),
+        ),
+    ]);
+    let children = |node: &MonsterNode| {
+        node.children
+            .iter()
+            .map(|c| c.borrow().data.monster_id.map(|id| id.0))
+            .collect::<Vec<Option<usize>>>()
+    };
+
+    let mut builder = MonsterTreeBuilder::new(lut);
+    let tree = builder.build(MonsterId(0));
+    assert_eq!(children(&tree), vec![Some(1), Some(2)]);
+
+    builder.limit_size(Some(BodySize::Large));
+    let tree = builder.build(MonsterId(0));
+    assert_eq!(children(&tree), vec![Some(2), Some(3)]);
+    // 3 can only be fused from a giant monster, so it becomes a material
+    assert_eq!(children(&tree.children[1].borrow()), vec![]);
+
+    // A monster with a four-parent recipe and no recorded size may be giant
+    let mut builder = MonsterTreeBuilder::new(HashMap::from([(
+        MonsterId(0),
+        monster(vec![vec![monster_parent(0); 4]], None),
+    )]));
+    builder.limit_size(Some(BodySize::Large));
+    assert!(!builder.fits_size(MonsterId(0)));
+    builder.limit_size(Some(BodySize::Giant));
+    assert!(builder.fits_size(MonsterId(0)));
+}
+
+#[test]
+fn test_builder_filters() {
+    use super::data::{Habitat, SeasonHabitat};
+
+    let monster = |rank: usize, parents: Vec<Vec<Parent>>, scoutable: bool| Monster {
+        habitats: match scoutable {
+            true => HashMap::from([(
+                AreaId(0),
+                Habitat {
+                    seasons: HashMap::from([(
+                        SeasonId(0),
+                        SeasonHabitat::new(&[true, false]).unwrap(),
+                    )]),
+                },
+            )]),
+            false => HashMap::new(),
+        },
+        ..test_monster(rank, 0, parents)
+    };
+    /*
+     * 0 +- 1 +- 4
+     *   |    +- 5
+     *   +- 5 (rank 7)
+     * 0 +- 1
+     *   +- 2
+     *   +- 3
+     *   +- 4
+     */
+    let lut = HashMap::from([
+        (
+            MonsterId(0),
+            monster(
+                2,
+                vec![
+                    vec![monster_parent(1), monster_parent(5)],
+                    vec![
+                        monster_parent(1),
+                        monster_parent(2),
+                        monster_parent(3),
+                        monster_parent(4),
+                    ],
+                ],
+                true,
+            ),
+        ),
+        (
+            MonsterId(1),
+            monster(3, vec![vec![monster_parent(4), monster_parent(5)]], true),
+        ),
+        (MonsterId(2), monster(3, vec![], false)),
+        (MonsterId(3), monster(3, vec![], false)),
+        (MonsterId(4), monster(3, vec![], false)),
+        (MonsterId(5), monster(7, vec![], false)),
+    ]);
+    let children = |node: &MonsterNode| {
+        node.children
+            .iter()
+            .map(|c| c.borrow().data.monster_id.map(|id| id.0))
+            .collect::<Vec<Option<usize>>>()
+    };
+
+    let mut builder = MonsterTreeBuilder::new(lut);
+    let tree = builder.build(MonsterId(0));
+    assert_eq!(children(&tree), vec![Some(1), Some(5)]);
+    assert_eq!(children(&tree.children[0].borrow()), vec![Some(4), Some(5)]);
+
+    // The target is fused even though it is scoutable
+    builder.stop_at_scoutable(true);
+    let tree = builder.build(MonsterId(0));
+    assert_eq!(children(&tree), vec![Some(1), Some(5)]);
+    assert_eq!(children(&tree.children[0].borrow()), vec![]);
+    builder.stop_at_scoutable(false);
+
+    // Rank 7 is out of the range, so only the four-parent recipe is left for 0 and 1 is a material
+    builder.limit_rank([Some(RankId(1)), Some(RankId(5))]);
+    let tree = builder.build(MonsterId(0));
+    assert_eq!(children(&tree), vec![Some(1), Some(2), Some(3), Some(4)]);
+    assert_eq!(children(&tree.children[0].borrow()), vec![]);
+    // The bounds may be given the other way around
+    builder.limit_rank([Some(RankId(5)), Some(RankId(1))]);
+    let tree = builder.build(MonsterId(0));
+    assert_eq!(children(&tree), vec![Some(1), Some(2), Some(3), Some(4)]);
+
+    builder.avoid_large_recipes(true);
+    let tree = builder.build(MonsterId(0));
+    assert_eq!(children(&tree), vec![]);
+    builder.limit_rank([None, None]);
+    let tree = builder.build(MonsterId(0));
+    assert_eq!(children(&tree), vec![Some(1), Some(5)]);
+}
+
+#[test]
+fn test_summarize_materials() {
+    /*
+     * 0 +- 1 +- family 1
+     *   |    +- family 0
+     *   |
+     *   +- 2 +- 3
+     *   |    +- family 2
+     *   |
+     *   +- 3
+     */
+    let lut: HashMap<MonsterId, Monster> = HashMap::from([
+        (
+            MonsterId(0),
+            test_monster(
+                1,
+                0,
+                vec![vec![
+                    monster_parent(1),
+                    monster_parent(2),
+                    monster_parent(3),
+                ]],
+            ),
+        ),
+        (
+            MonsterId(1),
+            test_monster(3, 0, vec![vec![family_parent(1), family_parent(0)]]),
+        ),
+        (
+            MonsterId(2),
+            test_monster(3, 0, vec![vec![monster_parent(3), family_parent(2)]]),
+        ),
+        (MonsterId(3), test_monster(5, 0, vec![])),
+    ]);
+
+    let tree = MonsterTreeBuilder::new(lut.clone()).build(MonsterId(0));
+    let actual = summarize_materials(&lut, Rc::new(RefCell::new(tree)));
+    let expected = vec![
+        MaterialSummary {
+            material: Material::Monster(MonsterId(3)),
+            count: 2,
+        },
+        MaterialSummary {
+            material: Material::Family {
+                families: vec![FamilyId(0), FamilyId(1)],
+                rank: Some(RankId(3)),
+            },
+            count: 1,
+        },
+        MaterialSummary {
+            material: Material::Family {
+                families: vec![FamilyId(2)],
+                rank: None,
+            },
+            count: 1,
+        },
+    ];
+    assert_eq!(actual, expected);
+
+    let leaf = MonsterTreeBuilder::new(lut.clone()).build(MonsterId(3));
+    let actual = summarize_materials(&lut, Rc::new(RefCell::new(leaf)));
+    assert_eq!(
+        actual,
+        vec![MaterialSummary {
+            material: Material::Monster(MonsterId(3)),
+            count: 1,
+        }]
+    );
+}
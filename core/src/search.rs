@@ -0,0 +1,252 @@
+use std::collections::HashMap;
+
+use unicode_normalization::UnicodeNormalization;
+
+use super::data::{Locale, Monster, MonsterId};
+
+/// How well a name matches a query. Better matches compare smaller.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum MatchKind {
+    Exact,
+    Prefix,
+    Substring,
+    Fuzzy(usize),
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct SearchHit {
+    pub monster: MonsterId,
+    pub kind: MatchKind,
+}
+
+fn fold_kana(c: char) -> char {
+    let c = match c {
+        // Hiragana to katakana
+        'ぁ'..='ゖ' | 'ゝ' | 'ゞ' => char::from_u32(c as u32 + 0x60).unwrap_or(c),
+        _ => c,
+    };
+    // Small kana to the normal size
+    match c {
+        'ァ' | 'ィ' | 'ゥ' | 'ェ' | 'ォ' | 'ッ' | 'ャ' | 'ュ' | 'ョ' | 'ヮ' => {
+            char::from_u32(c as u32 + 1).unwrap_or(c)
+        }
+        'ヵ' => 'カ',
+        'ヶ' => 'ケ',
+        _ => c,
+    }
+}
+
+fn is_ignored(c: char) -> bool {
+    // Long vowel marks and separators are often typed inconsistently
+    c.is_whitespace() || matches!(c, 'ー' | '-' | '‐' | '―' | '~' | '〜' | '・' | '.')
+}
+
+/// Full-width and half-width forms are unified by NFKC, hiragana is folded to katakana,
+/// small kana to normal kana, and long vowel marks and separators are dropped.
+pub fn normalize(text: &str) -> String {
+    text.nfkc()
+        .map(fold_kana)
+        .filter(|&c| !is_ignored(c))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+fn match_name(query: &str, name: &str) -> Option<MatchKind> {
+    if name == query {
+        return Some(MatchKind::Exact);
+    }
+    if name.starts_with(query) {
+        return Some(MatchKind::Prefix);
+    }
+    if name.contains(query) {
+        return Some(MatchKind::Substring);
+    }
+
+    let query = query.chars().collect::<Vec<char>>();
+    let name = name.chars().collect::<Vec<char>>();
+    // Allow one typo in short names and two in longer ones
+    let max_distance = match query.len() {
+        0..=1 => return None,
+        2..=4 => 1,
+        _ => 2,
+    };
+    match edit_distance(&query, &name) {
+        d if d <= max_distance => Some(MatchKind::Fuzzy(d)),
+        _ => None,
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub struct SearchIndex {
+    entries: Vec<(String, MonsterId)>,
+}
+
+impl SearchIndex {
+    pub fn new(monsters: &HashMap<MonsterId, Monster>) -> Self {
+        let mut entries = monsters
+            .iter()
+            .flat_map(|(&id, m)| {
+                Locale::ALL
+                    .into_iter()
+                    .map(move |l| (normalize(m.name.get(l)), id))
+            })
+            .collect::<Vec<(String, MonsterId)>>();
+        entries.sort();
+        entries.dedup();
+        Self { entries }
+    }
+
+    /// Best matches come first. Ties are broken by the shorter name and then by the id.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SearchHit> {
+        let query = normalize(query);
+        if query.is_empty() {
+            return vec![];
+        }
+
+        let mut best: HashMap<MonsterId, (MatchKind, usize)> = HashMap::new();
+        for (name, id) in self.entries.iter() {
+            if let Some(kind) = match_name(&query, name) {
+                let key = (kind, name.chars().count());
+                best.entry(*id)
+                    .and_modify(|k| *k = (*k).min(key))
+                    .or_insert(key);
+            }
+        }
+
+        let mut hits = best
+            .into_iter()
+            .map(|(id, (kind, len))| (kind, len, id))
+            .collect::<Vec<_>>();
+        hits.sort();
+        hits.into_iter()
+            .take(limit)
+            .map(|(kind, _, monster)| SearchHit { monster, kind })
+            .collect()
+    }
+
+    /// Only an exact match up to normalization resolves.
+    /// Use `search` to suggest names for a query that does not resolve.
+    pub fn resolve(&self, query: &str) -> Option<MonsterId> {
+        self.search(query, 1)
+            .into_iter()
+            .find(|hit| hit.kind == MatchKind::Exact)
+            .map(|hit| hit.monster)
+    }
+}
+
+#[test]
+fn test_normalize() {
+    assert_eq!(normalize("スライム"), "スライム");
+    assert_eq!(normalize("すらいむ"), "スライム");
+    assert_eq!(normalize("ｽﾗｲﾑ"), "スライム");
+    assert_eq!(normalize("ホイミスライム"), normalize("ほいみすらいむ"));
+    assert_eq!(normalize("メタルキング"), normalize("メタルキンク\u{3099}"));
+    assert_eq!(normalize("キャット"), normalize("キヤツト"));
+    assert_eq!(normalize("ドラキーマ"), "ドラキマ");
+    assert_eq!(normalize("Ｓｌｉｍｅ Knight"), "slimeknight");
+}
+
+#[test]
+fn test_edit_distance() {
+    let chars = |s: &str| s.chars().collect::<Vec<char>>();
+    assert_eq!(edit_distance(&chars("スライム"), &chars("スライム")), 0);
+    assert_eq!(edit_distance(&chars("スライヌ"), &chars("スライム")), 1);
+    assert_eq!(edit_distance(&chars("スライ"), &chars("スライム")), 1);
+    assert_eq!(edit_distance(&chars(""), &chars("スライム")), 4);
+}
+
+#[cfg(test)]
+fn test_monster(ja: &str, en: Option<&str>) -> Monster {
+    use super::data::{FamilyId, LocalizedName, MonsterDetails, RankId};
+
+    Monster {
+        name: LocalizedName {
+            ja: ja.to_string(),
+            en: en.map(|s| s.to_string()),
+        },
+        rank: RankId(0),
+        family: FamilyId(0),
+        parents: vec![],
+        habitats: HashMap::new(),
+        details: MonsterDetails::default(),
+    }
+}
+
+#[test]
+fn test_search_index() {
+    let index = SearchIndex::new(&HashMap::from([
+        (MonsterId(0), test_monster("スライム", Some("Slime"))),
+        (
+            MonsterId(1),
+            test_monster("スライムベス", Some("She-slime")),
+        ),
+        (MonsterId(2), test_monster("ホイミスライム", None)),
+        (MonsterId(3), test_monster("ドラキー", None)),
+    ]));
+
+    assert_eq!(
+        index.search("すらいむ", 10),
+        vec![
+            SearchHit {
+                monster: MonsterId(0),
+                kind: MatchKind::Exact,
+            },
+            SearchHit {
+                monster: MonsterId(1),
+                kind: MatchKind::Prefix,
+            },
+            SearchHit {
+                monster: MonsterId(2),
+                kind: MatchKind::Substring,
+            },
+        ]
+    );
+    assert_eq!(index.search("すらいむ", 1).len(), 1);
+    assert_eq!(
+        index.search("she slime", 10),
+        vec![SearchHit {
+            monster: MonsterId(1),
+            kind: MatchKind::Exact,
+        }]
+    );
+    assert_eq!(
+        index.search("ドラキ", 10),
+        vec![SearchHit {
+            monster: MonsterId(3),
+            kind: MatchKind::Exact,
+        }]
+    );
+    assert_eq!(index.resolve("どらきー"), Some(MonsterId(3)));
+    assert_eq!(index.resolve("ドラギー"), None);
+    assert_eq!(index.resolve("スライ"), None);
+    assert_eq!(index.resolve("ゴーレム"), None);
+    assert_eq!(index.search("", 10), vec![]);
+}
+
+#[test]
+fn test_resolve_embedded_names() {
+    let monsters = super::data::get_monster_data();
+    let index = SearchIndex::new(monsters);
+    for (&id, monster) in monsters.iter() {
+        assert_eq!(
+            index.resolve(&monster.name.ja),
+            Some(id),
+            "{:}",
+            monster.name.ja
+        );
+    }
+}
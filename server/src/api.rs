@@ -0,0 +1,430 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use dqm3_fusion_core::data::{
+    lookup_name, AreaId, BaseStats, BodySize, GameData, Locale, LocalizedName, MonsterId, Parent,
+    SeasonId,
+};
+use dqm3_fusion_core::export::{
+    get_material_rows, get_monster_row, get_monster_rows, get_tree_row, MonsterRow,
+};
+use dqm3_fusion_core::recipe::{body_size, is_scoutable_in, used_in, MonsterTreeBuilder};
+use dqm3_fusion_core::search::{normalize, SearchIndex};
+
+const DEFAULT_SEARCH_LIMIT: usize = 10;
+const SUGGESTION_LIMIT: usize = 3;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ApiError {
+    NotFound(String),
+    UnknownMonster {
+        key: String,
+        suggestions: Vec<MonsterRow>,
+    },
+    BadRequest(String),
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound(message) | Self::BadRequest(message) => write!(f, "{:}", message),
+            Self::UnknownMonster { key, .. } => write!(f, "no monster is named \"{:}\"", key),
+        }
+    }
+}
+
+#[derive(Deserialize, Default, Debug)]
+pub struct ApiQuery {
+    pub locale: Option<String>,
+    pub name: Option<String>,
+    pub limit: Option<usize>,
+    pub prefer_standard: Option<bool>,
+    pub max_size: Option<BodySize>,
+    pub area: Option<String>,
+    pub season: Option<String>,
+}
+
+impl ApiQuery {
+    fn locale(&self) -> Result<Locale, ApiError> {
+        match &self.locale {
+            Some(code) => Locale::from_code(code).ok_or(ApiError::BadRequest(format!(
+                "unknown locale \"{:}\"",
+                code
+            ))),
+            None => Ok(Locale::default()),
+        }
+    }
+}
+
+pub struct Api {
+    data: GameData,
+    index: SearchIndex,
+}
+
+#[derive(Serialize, PartialEq, Debug)]
+struct ParentRow {
+    #[serde(flatten)]
+    parent: Parent,
+    name: String,
+}
+
+#[derive(Serialize, PartialEq, Debug)]
+struct SeasonRow {
+    id: SeasonId,
+    name: String,
+    flags: Vec<bool>,
+}
+
+#[derive(Serialize, PartialEq, Debug)]
+struct HabitatRow {
+    id: AreaId,
+    name: String,
+    seasons: Vec<SeasonRow>,
+}
+
+#[derive(Serialize, PartialEq, Debug)]
+struct MonsterDetailRow {
+    #[serde(flatten)]
+    monster: MonsterRow,
+    /// `None` for a monster that may be large or giant, see `body_size`.
+    size: Option<BodySize>,
+    stats: Option<BaseStats>,
+    traits: Vec<String>,
+    skills: Vec<String>,
+    recipes: Vec<Vec<ParentRow>>,
+    habitats: Vec<HabitatRow>,
+}
+
+#[derive(Serialize, PartialEq, Debug)]
+struct EncounterRow {
+    #[serde(flatten)]
+    monster: MonsterRow,
+    flags: Vec<bool>,
+}
+
+fn to_value<T: Serialize>(value: &T) -> Value {
+    serde_json::to_value(value).unwrap()
+}
+
+/// Find a key of `table` by its id or by its name in any locale.
+fn find_key<K: Copy + From<usize> + std::hash::Hash + Eq>(
+    table: &HashMap<K, LocalizedName>,
+    key: &str,
+) -> Option<K> {
+    if let Ok(id) = key.parse::<usize>() {
+        return Some(K::from(id)).filter(|id| table.contains_key(id));
+    }
+    let key = normalize(key);
+    table
+        .iter()
+        .find(|(_, name)| {
+            Locale::ALL
+                .into_iter()
+                .any(|l| normalize(name.get(l)) == key)
+        })
+        .map(|(&id, _)| id)
+}
+
+impl Api {
+    pub fn new(data: GameData) -> Self {
+        Self {
+            index: SearchIndex::new(&data.monsters),
+            data,
+        }
+    }
+
+    /// The monster `key` refers to, either by its id or by its exact name in any locale.
+    /// An unknown name fails with the closest names, in `locale`.
+    pub fn resolve(&self, key: &str, locale: Locale) -> Result<MonsterId, ApiError> {
+        if let Ok(id) = key.parse::<usize>() {
+            return Some(MonsterId(id))
+                .filter(|id| self.data.monsters.contains_key(id))
+                .ok_or(ApiError::NotFound(format!(
+                    "no monster matches \"{:}\"",
+                    key
+                )));
+        }
+        self.index.resolve(key).ok_or_else(|| {
+            let ids = self
+                .index
+                .search(key, SUGGESTION_LIMIT)
+                .into_iter()
+                .map(|hit| hit.monster)
+                .collect::<Vec<MonsterId>>();
+            ApiError::UnknownMonster {
+                key: key.to_string(),
+                suggestions: get_monster_rows(&ids, &self.data, locale),
+            }
+        })
+    }
+
+    fn tree_builder(&self, query: &ApiQuery) -> MonsterTreeBuilder {
+        let mut tree_builder = MonsterTreeBuilder::new(self.data.monsters.clone());
+        tree_builder.prefer_standard_fusion(query.prefer_standard.unwrap_or(false));
+        tree_builder.limit_size(query.max_size);
+        tree_builder
+    }
+
+    pub fn search(&self, query: &ApiQuery) -> Result<Value, ApiError> {
+        let locale = query.locale()?;
+        let name = query
+            .name
+            .as_deref()
+            .ok_or(ApiError::BadRequest("name is required".to_string()))?;
+        let ids = self
+            .index
+            .search(name, query.limit.unwrap_or(DEFAULT_SEARCH_LIMIT))
+            .into_iter()
+            .map(|hit| hit.monster)
+            .collect::<Vec<MonsterId>>();
+        Ok(to_value(&get_monster_rows(&ids, &self.data, locale)))
+    }
+
+    pub fn monster(&self, key: &str, query: &ApiQuery) -> Result<Value, ApiError> {
+        let locale = query.locale()?;
+        let id = self.resolve(key, locale)?;
+        let data = &self.data;
+        let monster = &data.monsters[&id];
+
+        let recipes = monster
+            .parents
+            .iter()
+            .map(|parents| {
+                parents
+                    .iter()
+                    .map(|parent| ParentRow {
+                        parent: parent.clone(),
+                        name: match (parent.monster, parent.family) {
+                            (Some(m), _) => data
+                                .monsters
+                                .get(&m)
+                                .map(|m| m.name.get(locale))
+                                .unwrap_or_default(),
+                            (None, Some(f)) => {
+                                lookup_name(&data.families, &f, locale).unwrap_or_default()
+                            }
+                            (None, None) => "",
+                        }
+                        .to_string(),
+                    })
+                    .collect()
+            })
+            .collect();
+        let mut habitats = monster
+            .habitats
+            .iter()
+            .map(|(&area, habitat)| {
+                let mut seasons = habitat
+                    .seasons
+                    .iter()
+                    .map(|(&season, h)| SeasonRow {
+                        id: season,
+                        name: lookup_name(&data.seasons, &season, locale)
+                            .unwrap_or_default()
+                            .to_string(),
+                        flags: h.flags().to_vec(),
+                    })
+                    .collect::<Vec<SeasonRow>>();
+                seasons.sort_by_key(|s| s.id);
+                HabitatRow {
+                    id: area,
+                    name: lookup_name(&data.areas, &area, locale)
+                        .unwrap_or_default()
+                        .to_string(),
+                    seasons,
+                }
+            })
+            .collect::<Vec<HabitatRow>>();
+        habitats.sort_by_key(|h| h.id);
+
+        Ok(to_value(&MonsterDetailRow {
+            monster: get_monster_row(id, data, locale).unwrap(),
+            size: body_size(monster),
+            stats: monster.details.stats,
+            traits: monster
+                .details
+                .traits
+                .iter()
+                .map(|t| t.get(locale).to_string())
+                .collect(),
+            skills: monster
+                .details
+                .skills
+                .iter()
+                .map(|s| s.get(locale).to_string())
+                .collect(),
+            recipes,
+            habitats,
+        }))
+    }
+
+    pub fn tree(&self, key: &str, query: &ApiQuery) -> Result<Value, ApiError> {
+        let locale = query.locale()?;
+        let tree = self.tree_builder(query).build(self.resolve(key, locale)?);
+        let row = get_tree_row(Rc::new(RefCell::new(tree)), &self.data, locale);
+        Ok(to_value(&row))
+    }
+
+    pub fn materials(&self, key: &str, query: &ApiQuery) -> Result<Value, ApiError> {
+        let locale = query.locale()?;
+        let tree = self.tree_builder(query).build(self.resolve(key, locale)?);
+        let rows = get_material_rows(Rc::new(RefCell::new(tree)), &self.data, locale);
+        Ok(to_value(&rows))
+    }
+
+    pub fn used_in(&self, key: &str, query: &ApiQuery) -> Result<Value, ApiError> {
+        let locale = query.locale()?;
+        let ids = used_in(&self.data.monsters, self.resolve(key, locale)?);
+        Ok(to_value(&get_monster_rows(&ids, &self.data, locale)))
+    }
+
+    /// Areas and seasons are given by their ids or names.
+    pub fn habitats(&self, query: &ApiQuery) -> Result<Value, ApiError> {
+        let locale = query.locale()?;
+        let data = &self.data;
+        let (Some(area), Some(season)) = (&query.area, &query.season) else {
+            return Err(ApiError::BadRequest(
+                "area and season are required".to_string(),
+            ));
+        };
+        let area_id = find_key::<AreaId>(&data.areas, area)
+            .ok_or(ApiError::NotFound(format!("no area matches \"{:}\"", area)))?;
+        let season_id = find_key::<SeasonId>(&data.seasons, season).ok_or(ApiError::NotFound(
+            format!("no season matches \"{:}\"", season),
+        ))?;
+
+        let mut ids = data
+            .monsters
+            .iter()
+            .filter(|(_, m)| is_scoutable_in(m, area_id, season_id))
+            .map(|(&id, _)| id)
+            .collect::<Vec<MonsterId>>();
+        ids.sort();
+        let rows = ids
+            .iter()
+            .map(|id| {
+                let monster = &data.monsters[id];
+                let encounter = monster.habitats[&area_id].seasons[&season_id];
+                EncounterRow {
+                    monster: get_monster_row(*id, data, locale).unwrap(),
+                    flags: encounter.flags().to_vec(),
+                }
+            })
+            .collect::<Vec<EncounterRow>>();
+        Ok(to_value(&rows))
+    }
+}
+
+#[test]
+fn test_resolve() {
+    let api = Api::new(GameData::embedded().unwrap().clone());
+    let slime = api.resolve("すらいむ", Locale::Ja).unwrap();
+    assert_eq!(api.resolve(&slime.to_string(), Locale::Ja), Ok(slime));
+    assert_eq!(
+        api.resolve("0", Locale::Ja),
+        Err(ApiError::NotFound("no monster matches \"0\"".to_string()))
+    );
+    match api.resolve("スライヌ", Locale::Ja) {
+        Err(ApiError::UnknownMonster { key, suggestions }) => {
+            assert_eq!(key, "スライヌ");
+            assert_eq!(suggestions[0].id, slime);
+        }
+        actual => panic!("{:?}", actual),
+    }
+    assert_eq!(
+        api.resolve("zzzzzzzzzzzzzzzz", Locale::Ja),
+        Err(ApiError::UnknownMonster {
+            key: "zzzzzzzzzzzzzzzz".to_string(),
+            suggestions: vec![],
+        })
+    );
+}
+
+#[test]
+fn test_monster() {
+    let api = Api::new(GameData::embedded().unwrap().clone());
+    let actual = api.monster("スライムベス", &ApiQuery::default()).unwrap();
+    assert_eq!(actual["name"], "スライムベス");
+    assert_eq!(actual["size"], "normal");
+    assert_eq!(
+        actual["recipes"],
+        serde_json::json!([
+            [
+                { "family": 0, "name": "スライム" },
+                { "family": 5, "name": "悪魔" },
+            ],
+            [
+                { "monster": 1, "name": "スライム" },
+                { "monster": 437, "name": "メラゴースト" },
+            ],
+        ])
+    );
+
+    let query = ApiQuery {
+        locale: Some("fr".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(
+        api.monster("スライムベス", &query),
+        Err(ApiError::BadRequest("unknown locale \"fr\"".to_string()))
+    );
+}
+
+#[test]
+fn test_tree_and_materials() {
+    let api = Api::new(GameData::embedded().unwrap().clone());
+    let query = ApiQuery::default();
+    let tree = api.tree("キングスライム", &query).unwrap();
+    assert_eq!(tree["name"], "キングスライム");
+    assert!(!tree["children"].as_array().unwrap().is_empty());
+
+    let materials = api.materials("キングスライム", &query).unwrap();
+    assert!(materials
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|m| m["name"] == "スライム"));
+}
+
+#[test]
+fn test_used_in() {
+    let api = Api::new(GameData::embedded().unwrap().clone());
+    let actual = api.used_in("スライム", &ApiQuery::default()).unwrap();
+    assert!(actual
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|m| m["name"] == "スライムベス"));
+}
+
+#[test]
+fn test_habitats() {
+    let api = Api::new(GameData::embedded().unwrap().clone());
+    let query = ApiQuery {
+        area: Some("人間界・平原".to_string()),
+        season: Some("0".to_string()),
+        ..Default::default()
+    };
+    let actual = api.habitats(&query).unwrap();
+    assert!(actual
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|m| m["name"] == "スライム"));
+
+    let query = ApiQuery {
+        area: Some("人間界・平原".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(
+        api.habitats(&query),
+        Err(ApiError::BadRequest(
+            "area and season are required".to_string()
+        ))
+    );
+}
@@ -0,0 +1,194 @@
+//! Serve the recipe engine as JSON endpoints for bots and scripts.
+//!
+//! Usage: `cargo run -p dqm3_fusion_server -- [--data-dir DIR] [--addr ADDR]`
+//!
+//! Endpoints, each taking `locale=ja|en`:
+//! - `GET /monsters?name=...&limit=N`: search monsters by name
+//! - `GET /monsters/{monster}`: rank, family, size, stats, recipes and habitats
+//! - `GET /monsters/{monster}/tree?prefer_standard=true&max_size=normal|large|giant`
+//! - `GET /monsters/{monster}/materials`: materials of the tree, with the same options
+//! - `GET /monsters/{monster}/used-in`: monsters with a recipe that takes the monster
+//! - `GET /habitats?area=...&season=...`: monsters to scout in an area during a season
+//!
+//! `{monster}` is an id or an exact name in either locale. For an unknown name, the 404 response
+//! lists the closest monsters in `suggestions`.
+//!
+//! The embedded dataset is served unless `--data-dir` points to a directory with the same data
+//! files as `core/src/data`: `meta.json`, `monster.json`, `family.json`, `rank.json`,
+//! `area.json` and `season.json`, and optionally `lang.json`.
+//! The server listens on `127.0.0.1:8080` by default.
+
+mod api;
+
+use std::fs;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Arc;
+
+use axum::extract::{Path as UrlPath, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde_json::Value;
+
+use api::{Api, ApiError, ApiQuery};
+use dqm3_fusion_core::data::{
+    parse_family_meta_json, parse_id_name_json, parse_lang_json, parse_meta_json,
+    parse_monster_json, DataError, GameData,
+};
+
+const USAGE: &str = "usage: dqm3_fusion_server [--data-dir DIR] [--addr ADDR]";
+
+struct Args {
+    data_dir: Option<PathBuf>,
+    addr: SocketAddr,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut data_dir = None;
+    let mut addr = SocketAddr::from(([127, 0, 0, 1], 8080));
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--data-dir" => data_dir = Some(args.next().ok_or(USAGE)?.into()),
+            "--addr" => addr = args.next().and_then(|a| a.parse().ok()).ok_or(USAGE)?,
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+    Ok(Args { data_dir, addr })
+}
+
+fn read_file(dir: &Path, file: &str) -> Result<String, String> {
+    let path = dir.join(file);
+    fs::read_to_string(&path).map_err(|e| format!("{:}: {:}", path.display(), e))
+}
+
+/// Load every table from the data files in `dir`. `lang.json` holds the labels of the outputs
+/// rather than game data, so the embedded one is used when `dir` has none.
+fn load_data_dir(dir: &Path) -> Result<GameData, String> {
+    fn load<T>(
+        dir: &Path,
+        file: &str,
+        parse: impl Fn(&str, &str) -> Result<T, DataError>,
+    ) -> Result<T, String> {
+        let json = read_file(dir, file)?;
+        parse(file, &json).map_err(|e| e.to_string())
+    }
+
+    let lang = match dir.join("lang.json").exists() {
+        true => load(dir, "lang.json", parse_lang_json)?,
+        false => GameData::embedded()
+            .map_err(|e| e.to_string())?
+            .lang
+            .clone(),
+    };
+    let data = GameData {
+        meta: load(dir, "meta.json", parse_meta_json)?,
+        monsters: load(dir, "monster.json", parse_monster_json)?,
+        families: load(dir, "family.json", parse_id_name_json)?,
+        family_meta: load(dir, "family.json", parse_family_meta_json)?,
+        ranks: load(dir, "rank.json", parse_id_name_json)?,
+        areas: load(dir, "area.json", parse_id_name_json)?,
+        seasons: load(dir, "season.json", parse_id_name_json)?,
+        lang,
+    };
+    data.check().map_err(|e| e.to_string())?;
+    Ok(data)
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            Self::NotFound(_) | Self::UnknownMonster { .. } => StatusCode::NOT_FOUND,
+            Self::BadRequest(_) => StatusCode::BAD_REQUEST,
+        };
+        let body = match &self {
+            Self::UnknownMonster { suggestions, .. } => {
+                serde_json::json!({ "error": self.to_string(), "suggestions": suggestions })
+            }
+            _ => serde_json::json!({ "error": self.to_string() }),
+        };
+        (status, Json(body)).into_response()
+    }
+}
+
+type ApiState = State<Arc<Api>>;
+type ApiResult = Result<Json<Value>, ApiError>;
+
+async fn search(State(api): ApiState, Query(query): Query<ApiQuery>) -> ApiResult {
+    api.search(&query).map(Json)
+}
+
+async fn monster(
+    State(api): ApiState,
+    UrlPath(key): UrlPath<String>,
+    Query(query): Query<ApiQuery>,
+) -> ApiResult {
+    api.monster(&key, &query).map(Json)
+}
+
+async fn tree(
+    State(api): ApiState,
+    UrlPath(key): UrlPath<String>,
+    Query(query): Query<ApiQuery>,
+) -> ApiResult {
+    api.tree(&key, &query).map(Json)
+}
+
+async fn materials(
+    State(api): ApiState,
+    UrlPath(key): UrlPath<String>,
+    Query(query): Query<ApiQuery>,
+) -> ApiResult {
+    api.materials(&key, &query).map(Json)
+}
+
+async fn used_in(
+    State(api): ApiState,
+    UrlPath(key): UrlPath<String>,
+    Query(query): Query<ApiQuery>,
+) -> ApiResult {
+    api.used_in(&key, &query).map(Json)
+}
+
+async fn habitats(State(api): ApiState, Query(query): Query<ApiQuery>) -> ApiResult {
+    api.habitats(&query).map(Json)
+}
+
+async fn run(args: Args) -> Result<(), String> {
+    let data = match &args.data_dir {
+        Some(dir) => load_data_dir(dir)?,
+        None => GameData::embedded().map_err(|e| e.to_string())?.clone(),
+    };
+    let app = Router::new()
+        .route("/monsters", get(search))
+        .route("/monsters/{monster}", get(monster))
+        .route("/monsters/{monster}/tree", get(tree))
+        .route("/monsters/{monster}/materials", get(materials))
+        .route("/monsters/{monster}/used-in", get(used_in))
+        .route("/habitats", get(habitats))
+        .with_state(Arc::new(Api::new(data)));
+
+    let listener = tokio::net::TcpListener::bind(args.addr)
+        .await
+        .map_err(|e| format!("{:}: {:}", args.addr, e))?;
+    eprintln!("listening on http://{:}", args.addr);
+    axum::serve(listener, app).await.map_err(|e| e.to_string())
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let result = match parse_args() {
+        Ok(args) => run(args).await,
+        Err(message) => Err(message),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("{:}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
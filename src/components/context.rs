@@ -0,0 +1,15 @@
+use std::rc::Rc;
+
+use yew::prelude::*;
+
+use super::super::data::{GameData, Locale};
+
+#[hook]
+pub fn use_game_data() -> Rc<GameData> {
+    use_context::<Rc<GameData>>().expect("GameData is provided by App")
+}
+
+#[hook]
+pub fn use_locale() -> Locale {
+    use_context::<Locale>().unwrap_or_default()
+}
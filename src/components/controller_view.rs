@@ -1,62 +1,217 @@
-use std::collections::HashMap;
-use std::rc::Rc;
-
 use wasm_bindgen::JsCast;
-use web_sys::{Event, HtmlInputElement};
+use web_sys::{Event, HtmlInputElement, HtmlSelectElement};
 use yew::prelude::*;
 use yew::{Callback, Properties};
 
-use crate::data::Monster;
+use crate::data::{lookup_name, BodySize, Locale, MonsterId, RankId};
 
-use super::super::data::get_lang_data;
 use super::cards::Card;
+use super::context::{use_game_data, use_locale};
+use super::monster_name_input::MonsterNameInput;
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Default)]
 pub struct SearchConditions {
-    pub monster_id: Option<usize>,
+    pub monster_id: Option<MonsterId>,
+    pub max_size: Option<BodySize>,
+    pub prefer_standard: bool,
+    /// Highest and lowest rank, in this order.
+    pub rank_range: [Option<RankId>; 2],
+    pub stop_at_scoutable: bool,
+    pub avoid_large_recipes: bool,
 }
 
+const SIZE_OPTIONS: [(&str, Option<BodySize>, &str); 3] = [
+    ("", None, "size_any"),
+    ("normal", Some(BodySize::Normal), "size_normal"),
+    ("large", Some(BodySize::Large), "size_large"),
+];
+
+const STRATEGY_OPTIONS: [(&str, bool, &str); 2] = [
+    ("special", false, "strategy_special"),
+    ("standard", true, "strategy_standard"),
+];
+
 #[derive(Properties, PartialEq)]
 pub struct ControllerViewProps {
     pub condition: SearchConditions,
-    pub monster_lut: Rc<HashMap<usize, Monster>>,
 
     pub onchange: Callback<SearchConditions>,
+    pub onlocalechange: Callback<Locale>,
+}
+
+fn select_value(e: &Event) -> Option<String> {
+    e.target()
+        .and_then(|t| t.dyn_into::<HtmlSelectElement>().ok())
+        .map(|sel| sel.value())
+}
+
+fn is_checked(e: &Event) -> bool {
+    e.target()
+        .and_then(|t| t.dyn_into::<HtmlInputElement>().ok())
+        .map(|inp| inp.checked())
+        .unwrap_or(false)
+}
+
+fn change_condition<E: 'static>(
+    props: &ControllerViewProps,
+    update: impl Fn(&mut SearchConditions, E) + 'static,
+) -> Callback<E> {
+    let onchange = props.onchange.clone();
+    let condition = props.condition.clone();
+    Callback::from(move |e: E| {
+        let mut condition = condition.clone();
+        update(&mut condition, e);
+        onchange.emit(condition);
+    })
 }
 
 #[function_component(ControllerView)]
 pub fn controller_view(props: &ControllerViewProps) -> Html {
-    let lang = get_lang_data();
-    let monster_ids: HashMap<String, usize> = props
-        .monster_lut
-        .iter()
-        .map(|(&k, v)| (v.name.clone(), k))
-        .collect();
-    let handle_id_change = props.onchange.clone();
+    let data = use_game_data();
+    let locale = use_locale();
+    let handle_locale_change = props.onlocalechange.clone();
+    let condition = &props.condition;
+    let mut ranks = data.ranks.keys().copied().collect::<Vec<RankId>>();
+    ranks.sort();
+    let rank_select = |bound: usize, id: &'static str, key: &str| {
+        html! {
+            <div class="col form-floating">
+                <select
+                    id={id}
+                    class="form-select"
+                    onchange={
+                        change_condition(props, move |c, e: Event| {
+                            let rank = select_value(&e).and_then(|v| v.parse().ok()).map(RankId);
+                            c.rank_range[bound] = rank;
+                        })
+                    }
+                >
+                    <option value="" selected={condition.rank_range[bound].is_none()}>
+                        {data.text("rank_any", locale).to_string()}
+                    </option>
+                    {
+                        ranks.iter().map(|rank| html! {
+                            <option
+                                value={rank.to_string()}
+                                selected={condition.rank_range[bound] == Some(*rank)}
+                            >
+                                {lookup_name(&data.ranks, rank, locale).unwrap_or_default().to_string()}
+                            </option>
+                        }).collect::<Html>()
+                    }
+                </select>
+                <label for={id}>{data.text(key, locale).to_string()}</label>
+            </div>
+        }
+    };
+    let switch = |id: &'static str, key: &str, checked: bool, onchange: Callback<Event>| {
+        html! {
+            <div class="form-check form-switch">
+                <input class="form-check-input" type="checkbox" role="switch" id={id} {checked} {onchange} />
+                <label class="form-check-label" for={id}>{data.text(key, locale).to_string()}</label>
+            </div>
+        }
+    };
 
     html! {
-        <Card header={lang["controller_header"].ja.clone()}>
-            <div class="form-floating">
-                <input
-                    type="text"
-                    id="monster_name_input"
-                    class="form-control"
+        <Card header={data.text("controller_header", locale).to_string()}>
+            <div class="form-floating mb-2">
+                <select
+                    id="locale_select"
+                    class="form-select"
                     onchange={
                         Callback::from(move |e: Event| {
-                            let target = e.target().and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
-                            match target {
-                                Some(inp) => handle_id_change.emit(SearchConditions {
-                                    monster_id: monster_ids.get(&inp.value()).copied(),
-                                }),
-                                _ => handle_id_change.emit(SearchConditions { monster_id: None })
+                            let target = e.target().and_then(|t| t.dyn_into::<HtmlSelectElement>().ok());
+                            if let Some(locale) = target.and_then(|sel| Locale::from_code(&sel.value())) {
+                                handle_locale_change.emit(locale);
                             }
                         })
                     }
-                />
-                <label for="monster_name_input">
-                    {lang["monster_name_input"].ja.clone()}
+                >
+                {
+                    Locale::ALL.into_iter().map(|l| html! {
+                        <option value={l.code()} selected={l == locale}>
+                            {l.native_name()}
+                        </option>
+                    }).collect::<Html>()
+                }
+                </select>
+                <label for="locale_select">
+                    {data.text("locale_select", locale).to_string()}
                 </label>
             </div>
+            <div class="form-floating mb-2">
+                <select
+                    id="max_size_select"
+                    class="form-select"
+                    onchange={
+                        change_condition(props, |c, e: Event| {
+                            let value = select_value(&e).unwrap_or_default();
+                            c.max_size = SIZE_OPTIONS
+                                .iter()
+                                .find(|(v, _, _)| *v == value)
+                                .and_then(|(_, size, _)| *size);
+                        })
+                    }
+                >
+                {
+                    SIZE_OPTIONS.iter().map(|(value, size, key)| html! {
+                        <option value={*value} selected={*size == condition.max_size}>
+                            {data.text(key, locale).to_string()}
+                        </option>
+                    }).collect::<Html>()
+                }
+                </select>
+                <label for="max_size_select">
+                    {data.text("max_size_select", locale).to_string()}
+                </label>
+            </div>
+            <div class="form-floating mb-2">
+                <select
+                    id="strategy_select"
+                    class="form-select"
+                    onchange={
+                        change_condition(props, |c, e: Event| {
+                            c.prefer_standard = select_value(&e).as_deref() == Some("standard");
+                        })
+                    }
+                >
+                {
+                    STRATEGY_OPTIONS.iter().map(|(value, prefer_standard, key)| html! {
+                        <option value={*value} selected={*prefer_standard == condition.prefer_standard}>
+                            {data.text(key, locale).to_string()}
+                        </option>
+                    }).collect::<Html>()
+                }
+                </select>
+                <label for="strategy_select">
+                    {data.text("strategy_select", locale).to_string()}
+                </label>
+            </div>
+            <div class="row g-2">
+                {rank_select(0, "rank_highest_select", "rank_highest_select")}
+                {rank_select(1, "rank_lowest_select", "rank_lowest_select")}
+            </div>
+            <div class="form-text mb-2">{data.text("rank_range_note", locale).to_string()}</div>
+            <div class="mb-2">
+                {switch(
+                    "stop_at_scoutable_switch",
+                    "stop_at_scoutable",
+                    condition.stop_at_scoutable,
+                    change_condition(props, |c, e: Event| c.stop_at_scoutable = is_checked(&e)),
+                )}
+                {switch(
+                    "avoid_large_recipes_switch",
+                    "avoid_large_recipes",
+                    condition.avoid_large_recipes,
+                    change_condition(props, |c, e: Event| c.avoid_large_recipes = is_checked(&e)),
+                )}
+            </div>
+            <MonsterNameInput
+                onselect={
+                    change_condition(props, |c, monster_id: Option<MonsterId>| c.monster_id = monster_id)
+                }
+            />
         </Card>
     }
 }
@@ -0,0 +1,173 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gloo_file::callbacks::{read_as_text, FileReader};
+use wasm_bindgen::JsCast;
+use web_sys::{Event, HtmlInputElement};
+use yew::prelude::*;
+use yew::virtual_dom::AttrValue;
+use yew::{Callback, Properties};
+
+use super::super::data::{DatasetMeta, GameData, Locale};
+use super::super::lint::{LintIssue, Severity};
+use super::cards::Card;
+use super::context::{use_game_data, use_locale};
+
+#[derive(Clone, PartialEq)]
+pub struct DatasetFile {
+    pub name: String,
+    pub content: String,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct DatasetViewProps {
+    #[prop_or(None)]
+    pub source: Option<AttrValue>,
+    #[prop_or(None)]
+    pub error: Option<AttrValue>,
+    #[prop_or(Vec::new())]
+    pub issues: Vec<LintIssue>,
+
+    /// In stacking order.
+    #[prop_or(Vec::new())]
+    pub layers: Vec<(DatasetMeta, bool)>,
+
+    pub onload: Callback<DatasetFile>,
+    pub onloadlayer: Callback<DatasetFile>,
+    pub ontogglelayer: Callback<usize>,
+}
+
+fn describe_meta(meta: &DatasetMeta, data: &GameData, locale: Locale) -> String {
+    // A missing version is shown, so that an unversioned dataset is not mistaken for the latest
+    let version = match &meta.game_version {
+        Some(v) => format!("ver. {:}", v),
+        None => data.text("dataset_version_unknown", locale).to_string(),
+    };
+    let details = [Some(version), meta.source_date.clone()]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<String>>();
+    format!("{:} ({:})", meta.name.get(locale), details.join(", "))
+}
+
+fn read_file_callback(
+    reader: Rc<RefCell<Option<FileReader>>>,
+    onload: Callback<DatasetFile>,
+) -> Callback<Event> {
+    Callback::from(move |e: Event| {
+        let file = e
+            .target()
+            .and_then(|t| t.dyn_into::<HtmlInputElement>().ok())
+            .and_then(|inp| inp.files())
+            .and_then(|files| files.get(0));
+        if let Some(file) = file {
+            let file = gloo_file::File::from(file);
+            let name = file.name();
+            let onload = onload.clone();
+            *reader.borrow_mut() = Some(read_as_text(&file, move |res| match res {
+                Ok(content) => onload.emit(DatasetFile { name, content }),
+                Err(e) => log::error!("{:}: {:}", name, e),
+            }));
+        }
+    })
+}
+
+#[function_component(DatasetView)]
+pub fn dataset_view(props: &DatasetViewProps) -> Html {
+    let data = use_game_data();
+    let locale = use_locale();
+    // Reading is cancelled when the reader is dropped, so keep it across renders
+    let reader = use_mut_ref(|| None::<FileReader>);
+    let layer_reader = use_mut_ref(|| None::<FileReader>);
+
+    html! {
+        <Card header={data.text("dataset_header", locale).to_string()}>
+            <div class="mb-2">
+                <label for="dataset_file_input" class="form-label">
+                    {data.text("dataset_file_input", locale).to_string()}
+                </label>
+                <input
+                    type="file"
+                    accept=".json,application/json"
+                    id="dataset_file_input"
+                    class="form-control"
+                    onchange={read_file_callback(reader, props.onload.clone())}
+                />
+            </div>
+            <div>
+                {
+                    match &props.source {
+                        Some(source) => source.to_string(),
+                        None => format!(
+                            "{:}: {:}",
+                            data.text("dataset_embedded", locale),
+                            describe_meta(&data.meta, &data, locale)
+                        ),
+                    }
+                }
+            </div>
+            <div class="mt-2">
+                <label for="dataset_layer_input" class="form-label">
+                    {data.text("dataset_layer_input", locale).to_string()}
+                </label>
+                <input
+                    type="file"
+                    accept=".json,application/json"
+                    id="dataset_layer_input"
+                    class="form-control"
+                    onchange={read_file_callback(layer_reader, props.onloadlayer.clone())}
+                />
+            </div>
+            {
+                props.layers.iter().enumerate().map(|(i, (meta, active))| {
+                    let handle_toggle = props.ontogglelayer.clone();
+                    html! {
+                        <div class="form-check mt-1">
+                            <input
+                                type="checkbox"
+                                id={format!("dataset_layer_{:}", i)}
+                                class="form-check-input"
+                                checked={*active}
+                                onchange={Callback::from(move |_: Event| handle_toggle.emit(i))}
+                            />
+                            <label for={format!("dataset_layer_{:}", i)} class="form-check-label">
+                                {describe_meta(meta, &data, locale)}
+                            </label>
+                        </div>
+                    }
+                }).collect::<Html>()
+            }
+            {
+                match &props.error {
+                    Some(error) => html! {
+                        <div class="alert alert-danger mt-2 mb-0">
+                            {error}
+                        </div>
+                    },
+                    None => html! {},
+                }
+            }
+            {
+                match props.issues.is_empty() {
+                    true => html! {},
+                    false => html! {
+                        <ul class="list-group list-group-flush mt-2 small">
+                        {
+                            props.issues.iter().map(|issue| html! {
+                                <li class={
+                                    match issue.severity() {
+                                        Severity::Error => "list-group-item list-group-item-danger",
+                                        Severity::Warning => "list-group-item list-group-item-warning",
+                                    }
+                                }>
+                                    {issue.to_string()}
+                                </li>
+                            }).collect::<Html>()
+                        }
+                        </ul>
+                    },
+                }
+            }
+        </Card>
+    }
+}
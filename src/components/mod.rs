@@ -1,4 +1,4 @@
-mod cards;
+pub mod cards;
 mod monster_tree_node;
 mod monster_tree;
 mod monster_list_item;
@@ -6,3 +6,7 @@ mod monster_list;
 pub mod monster_tree_view;
 pub mod controller_view;
 pub mod monster_list_view;
+pub mod dataset_view;
+mod context;
+pub mod monster_detail_view;
+mod monster_name_input;
@@ -0,0 +1,121 @@
+use yew::prelude::*;
+use yew::Properties;
+
+use super::super::data::{lookup_name, BodySize, LocalizedName, MonsterId, SeasonId};
+use super::cards::Card;
+use super::context::{use_game_data, use_locale};
+
+#[derive(Properties, PartialEq)]
+pub struct MonsterDetailViewProps {
+    #[prop_or(None)]
+    pub monster: Option<MonsterId>,
+}
+
+#[function_component(MonsterDetailView)]
+pub fn monster_detail_view(props: &MonsterDetailViewProps) -> Html {
+    let data = use_game_data();
+    let locale = use_locale();
+    let text = |key: &str| data.text(key, locale).to_string();
+
+    let monster = match props.monster.and_then(|id| data.monsters.get(&id)) {
+        Some(monster) => monster,
+        None => return html! {},
+    };
+    let details = &monster.details;
+    let not_recorded = || html! { <span class="text-muted">{text("not_recorded")}</span> };
+    let names = |names: &Vec<LocalizedName>| match names.is_empty() {
+        true => not_recorded(),
+        false => html! {
+            names
+                .iter()
+                .map(|name| name.get(locale))
+                .collect::<Vec<&str>>()
+                .join(", ")
+        },
+    };
+    let row = |label: String, value: Html| {
+        html! {
+            <tr>
+                <th scope="row">{label}</th>
+                <td>{value}</td>
+            </tr>
+        }
+    };
+
+    let mut habitats = monster
+        .habitats
+        .iter()
+        .map(|(area, habitat)| {
+            let mut seasons = habitat.seasons.keys().copied().collect::<Vec<SeasonId>>();
+            seasons.sort();
+            let seasons = seasons
+                .iter()
+                .filter_map(|season| lookup_name(&data.seasons, season, locale))
+                .collect::<Vec<&str>>()
+                .join(", ");
+            let area = lookup_name(&data.areas, area, locale).unwrap_or_default();
+            (area, seasons)
+        })
+        .collect::<Vec<(&str, String)>>();
+    habitats.sort();
+
+    html! {
+        <Card header={text("monster_detail")}>
+            <h5 class="card-title">{monster.name.get(locale).to_string()}</h5>
+            <table class="table table-sm mb-0">
+                <tbody>
+                    {row(text("rank"), html! {
+                        lookup_name(&data.ranks, &monster.rank, locale).unwrap_or_default().to_string()
+                    })}
+                    {row(text("family"), html! {
+                        lookup_name(&data.families, &monster.family, locale).unwrap_or_default().to_string()
+                    })}
+                    {row(text("size"), match details.size {
+                        Some(BodySize::Normal) => html! { text("size_normal") },
+                        Some(BodySize::Large) => html! { text("size_large") },
+                        Some(BodySize::Giant) => html! { text("size_giant") },
+                        None => not_recorded(),
+                    })}
+                    {row(text("stats"), match details.stats {
+                        Some(stats) => html! {
+                            <table class="table table-sm table-borderless mb-0">
+                                <tbody>
+                                {
+                                    [
+                                        ("stat_hp", stats.hp),
+                                        ("stat_mp", stats.mp),
+                                        ("stat_attack", stats.attack),
+                                        ("stat_defense", stats.defense),
+                                        ("stat_agility", stats.agility),
+                                        ("stat_wisdom", stats.wisdom),
+                                    ].into_iter().map(|(key, value)| html! {
+                                        <tr>
+                                            <td>{text(key)}</td>
+                                            <td class="text-end">{value}</td>
+                                        </tr>
+                                    }).collect::<Html>()
+                                }
+                                </tbody>
+                            </table>
+                        },
+                        None => not_recorded(),
+                    })}
+                    {row(text("traits"), names(&details.traits))}
+                    {row(text("skills"), names(&details.skills))}
+                    {row(text("habitats"), match habitats.is_empty() {
+                        true => not_recorded(),
+                        false => html! {
+                            <ul class="list-unstyled mb-0">
+                            {
+                                habitats.iter().map(|(area, seasons)| html! {
+                                    <li>{format!("{:} ({:})", area, seasons)}</li>
+                                }).collect::<Html>()
+                            }
+                            </ul>
+                        },
+                    })}
+                </tbody>
+            </table>
+        </Card>
+    }
+}
@@ -1,14 +1,22 @@
 use yew::prelude::*;
 use yew::Properties;
 
+#[derive(Clone, PartialEq)]
+pub struct FamilyBadge {
+    pub label: AttrValue,
+    pub class: AttrValue,
+}
+
 #[derive(Properties, PartialEq)]
 pub struct MonsterListItemProps {
     #[prop_or(None)]
     pub name: Option<AttrValue>,
     #[prop_or(Vec::new())]
-    pub family: Vec<AttrValue>,
+    pub family: Vec<FamilyBadge>,
     #[prop_or(None)]
     pub rank: Option<AttrValue>,
+    #[prop_or(None)]
+    pub size: Option<AttrValue>,
     #[prop_or(1)]
     pub count: usize,
 }
@@ -21,11 +29,27 @@ pub fn monster_list_item(props: &MonsterListItemProps) -> Html {
                 <div class="col-8">
                 {
                     match &props.name {
-                        Some(name) => name.to_string(),
-                        None => match props.family.len() > 0 {
-                            true => props.family.join(" / "),
-                            _ => "".to_string(),
+                        Some(name) => html!{
+                            <span class="me-1">{name.to_string()}</span>
                         },
+                        None => html!{},
+                    }
+                }
+                {
+                    props.family.iter().map(|badge| html!{
+                        <span class={classes!("badge", "me-1", badge.class.to_string())}>
+                            {badge.label.to_string()}
+                        </span>
+                    }).collect::<Html>()
+                }
+                {
+                    match &props.size {
+                        Some(size) => html!{
+                            <span class="badge text-bg-warning ms-1">
+                                {size.to_string()}
+                            </span>
+                        },
+                        None => html!{},
                     }
                 }
                 </div>
@@ -1,32 +1,66 @@
 use std::cell::RefCell;
-use std::collections::HashMap;
 use std::rc::Rc;
 
 use yew::prelude::*;
 use yew::Properties;
 
-use super::super::data::Monster;
+use super::super::export::{materials_to_csv, materials_to_json};
 use super::super::recipe::MonsterNode;
 use super::cards::Card;
+use super::context::{use_game_data, use_locale};
 use super::monster_list::MonsterList;
 
 #[derive(Properties, PartialEq)]
 pub struct MonsterListViewProps {
     pub monster: Option<Rc<RefCell<MonsterNode>>>,
-    pub monster_lut: Rc<HashMap<usize, Monster>>,
+}
+
+fn to_data_url(mime: &str, content: &str) -> String {
+    format!(
+        "data:{:};charset=utf-8,{:}",
+        mime,
+        js_sys::encode_uri_component(content)
+    )
 }
 
 #[function_component(MonsterListView)]
 pub fn monster_list_view(props: &MonsterListViewProps) -> Html {
+    let data = use_game_data();
+    let locale = use_locale();
+
     html! {
         <Card>
         {
             match &props.monster {
                 Some(monster) => html! {
-                    <MonsterList
-                        monster={monster.clone()}
-                        monster_lut={props.monster_lut.clone()}
-                    />
+                    <>
+                        <div class="btn-group btn-group-sm mb-2">
+                            <a
+                                class="btn btn-outline-secondary"
+                                download="materials.csv"
+                                href={to_data_url(
+                                    "text/csv",
+                                    // Prepend BOM so that spreadsheets detect UTF-8
+                                    &format!("\u{FEFF}{:}", materials_to_csv(monster.clone(), &data, locale)),
+                                )}
+                            >
+                                {data.text("download_csv", locale).to_string()}
+                            </a>
+                            <a
+                                class="btn btn-outline-secondary"
+                                download="materials.json"
+                                href={to_data_url(
+                                    "application/json",
+                                    &materials_to_json(monster.clone(), &data, locale),
+                                )}
+                            >
+                                {data.text("download_json", locale).to_string()}
+                            </a>
+                        </div>
+                        <MonsterList
+                            monster={monster.clone()}
+                        />
+                    </>
                 },
                 None => html! {},
             }
@@ -0,0 +1,255 @@
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlInputElement, InputEvent, KeyboardEvent};
+use yew::prelude::*;
+use yew::{Callback, Properties};
+
+use super::super::data::{lookup_name, MonsterId};
+use super::super::search::SearchIndex;
+use super::context::{use_game_data, use_locale};
+
+const SUGGESTION_LIMIT: usize = 10;
+const DID_YOU_MEAN_LIMIT: usize = 3;
+
+#[derive(Properties, PartialEq)]
+pub struct MonsterNameInputProps {
+    /// Called with `None` when the input is cleared.
+    pub onselect: Callback<Option<MonsterId>>,
+}
+
+/// A suggestion is picked by clicking it or with the arrow keys and Enter.
+/// Entering a name that matches no monster exactly keeps the current selection
+/// and offers the closest names instead.
+#[function_component(MonsterNameInput)]
+pub fn monster_name_input(props: &MonsterNameInputProps) -> Html {
+    let data = use_game_data();
+    let locale = use_locale();
+    let index = use_memo(data.clone(), |data| SearchIndex::new(&data.monsters));
+    let query = use_state(String::new);
+    let expanded = use_state(|| false);
+    let highlighted = use_state(|| None::<usize>);
+    // Entered text which did not match any name exactly
+    let unmatched = use_state(|| None::<String>);
+
+    let suggestions = index
+        .search(&query, SUGGESTION_LIMIT)
+        .into_iter()
+        .map(|hit| hit.monster)
+        .collect::<Vec<MonsterId>>();
+
+    let select = {
+        let data = data.clone();
+        let query = query.setter();
+        let expanded = expanded.setter();
+        let highlighted = highlighted.setter();
+        let unmatched = unmatched.setter();
+        let onselect = props.onselect.clone();
+        Callback::from(move |id: MonsterId| {
+            if let Some(monster) = data.monsters.get(&id) {
+                query.set(monster.name.get(locale).to_string());
+            }
+            expanded.set(false);
+            highlighted.set(None);
+            unmatched.set(None);
+            onselect.emit(Some(id));
+        })
+    };
+    let commit = {
+        let index = index.clone();
+        let select = select.clone();
+        let expanded = expanded.setter();
+        let unmatched = unmatched.setter();
+        let onselect = props.onselect.clone();
+        Callback::from(move |text: String| {
+            let text = text.trim();
+            expanded.set(false);
+            if text.is_empty() {
+                unmatched.set(None);
+                onselect.emit(None);
+                return;
+            }
+            match index.resolve(text) {
+                Some(id) => select.emit(id),
+                None => unmatched.set(Some(text.to_string())),
+            }
+        })
+    };
+
+    let handle_input = {
+        let query = query.setter();
+        let expanded = expanded.setter();
+        let highlighted = highlighted.setter();
+        Callback::from(move |e: InputEvent| {
+            if let Some(inp) = e
+                .target()
+                .and_then(|t| t.dyn_into::<HtmlInputElement>().ok())
+            {
+                query.set(inp.value());
+                expanded.set(true);
+                highlighted.set(None);
+            }
+        })
+    };
+    let handle_keydown = {
+        let query = query.clone();
+        let expanded = expanded.clone();
+        let highlighted = highlighted.clone();
+        let suggestions = suggestions.clone();
+        let select = select.clone();
+        let commit = commit.clone();
+        Callback::from(move |e: KeyboardEvent| {
+            let count = suggestions.len();
+            match e.key().as_str() {
+                key @ ("ArrowDown" | "ArrowUp") if count > 0 => {
+                    e.prevent_default();
+                    let next = match (key, *highlighted) {
+                        ("ArrowDown", Some(i)) => (i + 1) % count,
+                        ("ArrowDown", None) => 0,
+                        (_, Some(i)) => (i + count - 1) % count,
+                        (_, None) => count - 1,
+                    };
+                    expanded.set(true);
+                    highlighted.set(Some(next));
+                }
+                "Enter" => {
+                    e.prevent_default();
+                    match (*expanded, *highlighted) {
+                        (true, Some(i)) if i < count => select.emit(suggestions[i]),
+                        _ => commit.emit((*query).clone()),
+                    }
+                }
+                "Escape" => {
+                    expanded.set(false);
+                    highlighted.set(None);
+                }
+                _ => (),
+            }
+        })
+    };
+    let handle_blur = {
+        let query = query.clone();
+        let expanded = expanded.clone();
+        let commit = commit.clone();
+        // Only commit what was typed since the last choice
+        Callback::from(move |_: FocusEvent| {
+            if *expanded {
+                commit.emit((*query).clone());
+            }
+        })
+    };
+
+    let show_suggestions = *expanded && !suggestions.is_empty();
+    let rank_name = |id: &MonsterId| {
+        data.monsters
+            .get(id)
+            .and_then(|m| lookup_name(&data.ranks, &m.rank, locale))
+            .unwrap_or_default()
+            .to_string()
+    };
+
+    html! {
+        <>
+            <div class="form-floating">
+                <input
+                    type="text"
+                    id="monster_name_input"
+                    class="form-control"
+                    autocomplete="off"
+                    role="combobox"
+                    aria-autocomplete="list"
+                    aria-controls="monster_name_suggestions"
+                    aria-expanded={show_suggestions.to_string()}
+                    aria-activedescendant={
+                        highlighted.map(|i| format!("monster_name_suggestion_{:}", i))
+                    }
+                    value={(*query).clone()}
+                    oninput={handle_input}
+                    onkeydown={handle_keydown}
+                    onblur={handle_blur}
+                />
+                <label for="monster_name_input">
+                    {data.text("monster_name_input", locale).to_string()}
+                </label>
+                if show_suggestions {
+                    <ul
+                        id="monster_name_suggestions"
+                        class="list-group position-absolute w-100 shadow"
+                        style="z-index: 1000;"
+                        role="listbox"
+                    >
+                    {
+                        suggestions.iter().enumerate().filter_map(|(i, id)| {
+                            data.monsters.get(id).map(|m| (i, *id, m))
+                        }).map(|(i, id, m)| {
+                            let active = *highlighted == Some(i);
+                            let select = select.clone();
+                            html! {
+                                <li
+                                    id={format!("monster_name_suggestion_{:}", i)}
+                                    class={classes!(
+                                        "list-group-item",
+                                        "list-group-item-action",
+                                        "d-flex",
+                                        "align-items-center",
+                                        active.then_some("active"),
+                                    )}
+                                    role="option"
+                                    aria-selected={active.to_string()}
+                                    onmousedown={
+                                        // Keep the focus so that the input is not committed on blur
+                                        Callback::from(move |e: MouseEvent| {
+                                            e.prevent_default();
+                                            select.emit(id);
+                                        })
+                                    }
+                                >
+                                    <span class={classes!("badge", "me-2", data.family_class(m.family))}>
+                                        {lookup_name(&data.families, &m.family, locale).unwrap_or_default().to_string()}
+                                    </span>
+                                    <span class="me-auto">{m.name.get(locale).to_string()}</span>
+                                    <span class="badge text-bg-primary">{rank_name(&id)}</span>
+                                </li>
+                            }
+                        }).collect::<Html>()
+                    }
+                    </ul>
+                }
+            </div>
+            {
+                match &*unmatched {
+                    Some(text) => html! {
+                        <div class="form-text text-danger">
+                            {format!("{:}: \"{:}\"", data.text("no_match", locale), text)}
+                            {
+                                match index.search(text, DID_YOU_MEAN_LIMIT).as_slice() {
+                                    [] => html! {},
+                                    hits => html! {
+                                        <>
+                                            {format!(" {:}: ", data.text("did_you_mean", locale))}
+                                            {
+                                                hits.iter().filter_map(|hit| {
+                                                    data.monsters.get(&hit.monster).map(|m| (hit.monster, m))
+                                                }).map(|(id, m)| {
+                                                    let select = select.clone();
+                                                    html! {
+                                                        <button
+                                                            type="button"
+                                                            class="btn btn-link btn-sm p-0 me-2 align-baseline"
+                                                            onclick={Callback::from(move |_: MouseEvent| select.emit(id))}
+                                                        >
+                                                            {m.name.get(locale).to_string()}
+                                                        </button>
+                                                    }
+                                                }).collect::<Html>()
+                                            }
+                                        </>
+                                    },
+                                }
+                            }
+                        </div>
+                    },
+                    None => html! {},
+                }
+            }
+        </>
+    }
+}
@@ -1,39 +1,45 @@
 use std::cell::RefCell;
-use std::collections::HashMap;
 use std::rc::Rc;
 use yew::prelude::*;
 use yew::Properties;
 
-use super::super::data::Monster;
-use super::super::recipe::MonsterNode;
-use super::monster_tree_node::MonsterTreeNode;
+use super::super::recipe::{body_size, MonsterNode};
+use super::context::{use_game_data, use_locale};
+use super::monster_tree_node::{size_label_key, MonsterTreeNode};
 
 #[derive(Properties, PartialEq)]
 pub struct MonsterTreeProps {
-    pub monster_lut: Rc<HashMap<usize, Monster>>,
     pub monster: Rc<RefCell<MonsterNode>>,
 }
 
 #[function_component(MonsterTree)]
+#[allow(clippy::manual_map)]
 pub fn monster_tree(props: &MonsterTreeProps) -> Html {
-    let monster = match props.monster.borrow().data.monster_id {
-        Some(id) => match props.monster_lut.get(&id) {
-            Some(m) => Some(m.name.clone()),
-            None => None,
-        },
-        None => None,
-    };
+    let data = use_game_data();
+    let locale = use_locale();
+    let monster = props
+        .monster
+        .borrow()
+        .data
+        .monster_id
+        .and_then(|id| data.monsters.get(&id));
+    let name = monster.map(|m| m.name.get(locale).to_string());
+    let size = monster.and_then(|m| size_label_key(body_size(m)));
     let family = match props.monster.borrow().data.spec.clone() {
         Some(spec) => Some(spec.family),
         None => None,
     };
     let children = props.monster.borrow().children.clone();
+    let four_parents = children.len() == 4;
 
     html! {
         <div>
             <MonsterTreeNode
-                monster={monster}
+                monster={name}
                 family={family}
+                monster_family={monster.map(|m| m.family)}
+                size={size}
+                four_parents={four_parents}
             />
             <div class="monster-nodes-container">
             {
@@ -41,7 +47,6 @@ pub fn monster_tree(props: &MonsterTreeProps) -> Html {
                     html! {
                         <div class="monster-node-container">
                             <MonsterTree
-                                monster_lut={props.monster_lut.clone()}
                                 monster={m.clone()}
                             />
                         </div>
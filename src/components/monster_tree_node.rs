@@ -2,8 +2,9 @@ use yew::prelude::*;
 use yew::virtual_dom::AttrValue;
 use yew::Properties;
 
-use super::super::data::get_family_data;
+use super::super::data::{lookup_name, BodySize, FamilyId};
 use super::cards::Card;
+use super::context::{use_game_data, use_locale};
 
 #[derive(Properties, PartialEq)]
 pub struct MonsterTreeNodeProps {
@@ -11,14 +12,51 @@ pub struct MonsterTreeNodeProps {
     pub monster: Option<AttrValue>,
 
     #[prop_or(None)]
-    pub family: Option<usize>,
+    pub family: Option<FamilyId>,
+
+    /// Colors the node unless `family` is set.
+    #[prop_or(None)]
+    pub monster_family: Option<FamilyId>,
+
+    /// See `size_label_key`.
+    #[prop_or(None)]
+    pub size: Option<&'static str>,
+
+    #[prop_or(false)]
+    pub four_parents: bool,
+}
+
+/// Normal size is not labeled.
+pub fn size_label_key(size: Option<BodySize>) -> Option<&'static str> {
+    match size {
+        Some(BodySize::Normal) => None,
+        Some(BodySize::Large) => Some("size_large"),
+        Some(BodySize::Giant) => Some("size_giant"),
+        None => Some("size_large_or_giant"),
+    }
 }
 
 #[function_component(MonsterTreeNode)]
 pub fn monster_tree_node(props: &MonsterTreeNodeProps) -> Html {
+    let locale = use_locale();
+    let data = use_game_data();
+    let color_family = props.family.or(props.monster_family);
+    let variant = match color_family {
+        Some(family) => format!("{:} monster-node", data.family_class(family)),
+        None => "color-dark monster-node".to_string(),
+    };
+
     html! {
-        <Card variant="color-dark monster-node">
+        <Card variant={variant}>
             <span class="monster-node-text">
+                {
+                    match color_family.and_then(|family| data.family_meta.get(&family)) {
+                        Some(meta) => html!{
+                            <span class="me-1">{meta.icon.clone()}</span>
+                        },
+                        None => html!{},
+                    }
+                }
                 {
                     match props.monster.clone() {
                         Some(name) => html!{name},
@@ -26,14 +64,37 @@ pub fn monster_tree_node(props: &MonsterTreeNodeProps) -> Html {
                     }
                 }
                 {
-                    match props.family.clone() {
-                        Some(family) => match get_family_data().get(&family) {
-                            Some(name) => html!{format!("{:} 系", name)},
+                    match props.family {
+                        Some(family) => match lookup_name(&data.families, &family, locale) {
+                            Some(name) => match data.text("family_suffix", locale) {
+                                "" => html!{name.to_string()},
+                                suffix => html!{format!("{:} {:}", name, suffix)},
+                            },
                             _ => html!{},
                         },
                         _ => html!{},
                     }
                 }
+                {
+                    match props.size {
+                        Some(key) => html!{
+                            <span class="badge text-bg-warning ms-1">
+                                {data.text(key, locale).to_string()}
+                            </span>
+                        },
+                        None => html!{},
+                    }
+                }
+                {
+                    match props.four_parents {
+                        true => html!{
+                            <span class="badge text-bg-info ms-1">
+                                {data.text("four_parent_recipe", locale).to_string()}
+                            </span>
+                        },
+                        false => html!{},
+                    }
+                }
             </span>
         </Card>
     }
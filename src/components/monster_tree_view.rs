@@ -1,27 +1,69 @@
 use std::cell::RefCell;
-use std::collections::HashMap;
 use std::rc::Rc;
 
 use yew::prelude::*;
 use yew::Properties;
 
-use super::super::data::{get_lang_data, Monster};
+use super::super::export::{to_markdown, to_mermaid};
 use super::super::recipe::MonsterNode;
 use super::cards::Card;
+use super::context::{use_game_data, use_locale};
 use super::monster_tree::MonsterTree;
 
 #[derive(Properties, PartialEq)]
 pub struct MonsterTreeViewProps {
     #[prop_or(None)]
     pub monster: Option<Rc<RefCell<MonsterNode>>>,
+}
 
-    pub monster_lut: Rc<HashMap<usize, Monster>>,
+fn copy_to_clipboard(text: &str) {
+    if let Some(window) = web_sys::window() {
+        let _ = window.navigator().clipboard().write_text(text);
+    }
 }
 
 #[function_component(MonsterTreeView)]
 pub fn monster_tree_view(props: &MonsterTreeViewProps) -> Html {
+    let data = use_game_data();
+    let locale = use_locale();
+
     html! {
-        <Card header={get_lang_data()["monster_tree"].ja.clone()}>
+        <Card header={data.text("monster_tree", locale).to_string()}>
+            {
+                match props.monster.clone() {
+                    Some(monster) => {
+                        let (markdown_monster, markdown_data) = (monster.clone(), data.clone());
+                        let (mermaid_monster, mermaid_data) = (monster, data.clone());
+                        html! {
+                            <div class="btn-group btn-group-sm mb-2">
+                                <button
+                                    type="button"
+                                    class="btn btn-outline-secondary"
+                                    onclick={
+                                        Callback::from(move |_: MouseEvent| {
+                                            copy_to_clipboard(&to_markdown(markdown_monster.clone(), &markdown_data, locale));
+                                        })
+                                    }
+                                >
+                                    {data.text("copy_markdown", locale).to_string()}
+                                </button>
+                                <button
+                                    type="button"
+                                    class="btn btn-outline-secondary"
+                                    onclick={
+                                        Callback::from(move |_: MouseEvent| {
+                                            copy_to_clipboard(&to_mermaid(mermaid_monster.clone(), &mermaid_data, locale));
+                                        })
+                                    }
+                                >
+                                    {data.text("copy_mermaid", locale).to_string()}
+                                </button>
+                            </div>
+                        }
+                    },
+                    None => html! {},
+                }
+            }
             <div class="monster-tree-container">
                 <div class="monster-nodes-container">
                 {
@@ -29,7 +71,6 @@ pub fn monster_tree_view(props: &MonsterTreeViewProps) -> Html {
                         Some(monster) => html! {
                             <MonsterTree
                                 monster={monster}
-                                monster_lut={props.monster_lut.clone()}
                             />
                         },
                         None => html! {},
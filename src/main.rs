@@ -2,26 +2,37 @@ use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
 
-use wasm_logger;
 use yew::prelude::*;
 use yew::Properties;
 
+use dqm3_fusion_core::{data, export, lint, recipe, search};
+
 mod components;
-mod data;
-mod recipe;
 
 #[derive(Properties, PartialEq, Default)]
 pub struct AppProps;
 
 pub enum AppMessage {
     ChangeSearchCondition(components::controller_view::SearchConditions),
+    LoadDataset(components::dataset_view::DatasetFile),
+    LoadLayer(components::dataset_view::DatasetFile),
+    ToggleLayer(usize),
+    ChangeLocale(data::Locale),
 }
 
 pub struct App {
     search_condition: components::controller_view::SearchConditions,
+    locale: data::Locale,
 
     tree_builder: recipe::MonsterTreeBuilder,
-    monster_lut: Rc<HashMap<usize, data::Monster>>,
+    data: Rc<data::GameData>,
+    /// Either embedded or loaded from `dataset_source`, before the layers are applied.
+    base_monsters: HashMap<data::MonsterId, data::Monster>,
+    layers: Vec<(data::Layer, bool)>,
+    dataset_source: Option<String>,
+    dataset_error: Option<String>,
+    dataset_issues: Vec<lint::LintIssue>,
+    load_error: Option<data::DataError>,
 }
 
 impl Component for App {
@@ -29,12 +40,25 @@ impl Component for App {
     type Properties = AppProps;
 
     fn create(_ctx: &Context<Self>) -> Self {
-        let monster_lut = data::get_monster_data();
+        let (data, load_error) = match data::GameData::embedded() {
+            Ok(data) => (data.clone(), None),
+            Err(e) => {
+                log::error!("{:}", e);
+                (data::GameData::default(), Some(e))
+            }
+        };
 
         App {
-            search_condition: components::controller_view::SearchConditions { monster_id: None },
-            tree_builder: recipe::MonsterTreeBuilder::new(monster_lut.clone()),
-            monster_lut: Rc::new(monster_lut),
+            search_condition: components::controller_view::SearchConditions::default(),
+            locale: browser_locale(),
+            tree_builder: recipe::MonsterTreeBuilder::new(data.monsters.clone()),
+            base_monsters: data.monsters.clone(),
+            data: Rc::new(data),
+            layers: vec![],
+            dataset_source: None,
+            dataset_error: None,
+            dataset_issues: vec![],
+            load_error,
         }
     }
 
@@ -42,40 +66,183 @@ impl Component for App {
         match msg {
             Self::Message::ChangeSearchCondition(cond) => {
                 self.search_condition = cond;
+                self.configure_tree_builder();
+                true
+            }
+            Self::Message::ChangeLocale(locale) => {
+                self.locale = locale;
+                true
+            }
+            Self::Message::LoadDataset(file) => {
+                match data::load_monster_data(&file.name, &file.content, &self.data) {
+                    Ok(monsters) => {
+                        let previous = std::mem::replace(&mut self.base_monsters, monsters);
+                        let previous_source = self.dataset_source.replace(file.name);
+                        if let Err(e) = self.apply_dataset() {
+                            self.base_monsters = previous;
+                            self.dataset_source = previous_source;
+                            self.dataset_error = Some(e.to_string());
+                        }
+                    }
+                    Err(e) => self.dataset_error = Some(e.to_string()),
+                }
+                true
+            }
+            Self::Message::LoadLayer(file) => {
+                match data::load_layer(&file.name, &file.content) {
+                    Ok(layer) => {
+                        self.layers.push((layer, true));
+                        if let Err(e) = self.apply_dataset() {
+                            self.layers.pop();
+                            self.dataset_error = Some(e.to_string());
+                        }
+                    }
+                    Err(e) => self.dataset_error = Some(e.to_string()),
+                }
+                true
+            }
+            Self::Message::ToggleLayer(index) => {
+                if let Some((_, active)) = self.layers.get_mut(index) {
+                    *active = !*active;
+                    if let Err(e) = self.apply_dataset() {
+                        // A later layer depends on this one
+                        self.layers[index].1 = !self.layers[index].1;
+                        self.dataset_error = Some(e.to_string());
+                    }
+                }
                 true
             }
         }
     }
 
+    #[allow(clippy::manual_map, clippy::redundant_closure)]
     fn view(&self, ctx: &Context<Self>) -> Html {
+        if let Some(e) = &self.load_error {
+            return html! {
+                <div class="container">
+                    <components::cards::Card
+                        variant="text-bg-danger"
+                        header={data::lookup_text(data::get_lang_data(), "load_error", self.locale).to_string()}
+                    >
+                        {e.to_string()}
+                    </components::cards::Card>
+                </div>
+            };
+        }
+
         let tree = match self.search_condition.monster_id {
             Some(id) => Some(Rc::new(RefCell::new(self.tree_builder.build(id)))),
             None => None,
         };
 
         html! {
-            <div class="container">
-                <components::controller_view::ControllerView
-                    condition={self.search_condition.clone()}
-                    monster_lut={self.monster_lut.clone()}
-                    onchange={
-                        ctx.link().callback(|cond| Self::Message::ChangeSearchCondition(cond))
-                    }
-                />
-                <components::monster_tree_view::MonsterTreeView
-                    monster_lut={self.monster_lut.clone()}
-                    monster={tree.clone()}
-                />
-                <components::monster_list_view::MonsterListView
-                    monster_lut={self.monster_lut.clone()}
-                    monster={tree.clone()}
-                />
-            </div>
+            <ContextProvider<Rc<data::GameData>> context={self.data.clone()}>
+                <ContextProvider<data::Locale> context={self.locale}>
+                    <div class="container">
+                        <components::controller_view::ControllerView
+                            condition={self.search_condition.clone()}
+                            onchange={
+                                ctx.link().callback(|cond| Self::Message::ChangeSearchCondition(cond))
+                            }
+                            onlocalechange={ctx.link().callback(Self::Message::ChangeLocale)}
+                        />
+                        <components::monster_detail_view::MonsterDetailView
+                            monster={self.search_condition.monster_id}
+                        />
+                        <components::monster_tree_view::MonsterTreeView
+                            monster={tree.clone()}
+                        />
+                        <components::monster_list_view::MonsterListView
+                            monster={tree.clone()}
+                        />
+                        <components::dataset_view::DatasetView
+                            source={self.dataset_source.clone().map(AttrValue::from)}
+                            error={self.dataset_error.clone().map(AttrValue::from)}
+                            issues={self.dataset_issues.clone()}
+                            layers={
+                                self.layers
+                                    .iter()
+                                    .map(|(layer, active)| (layer.meta.clone(), *active))
+                                    .collect::<Vec<_>>()
+                            }
+                            onload={ctx.link().callback(Self::Message::LoadDataset)}
+                            onloadlayer={ctx.link().callback(Self::Message::LoadLayer)}
+                            ontogglelayer={ctx.link().callback(Self::Message::ToggleLayer)}
+                        />
+                    </div>
+                </ContextProvider<data::Locale>>
+            </ContextProvider<Rc<data::GameData>>>
+        }
+    }
+}
+
+impl App {
+    fn configure_tree_builder(&mut self) {
+        let cond = &self.search_condition;
+        self.tree_builder.limit_size(cond.max_size);
+        self.tree_builder
+            .prefer_standard_fusion(cond.prefer_standard);
+        self.tree_builder.limit_rank(cond.rank_range);
+        self.tree_builder.stop_at_scoutable(cond.stop_at_scoutable);
+        self.tree_builder
+            .avoid_large_recipes(cond.avoid_large_recipes);
+    }
+
+    /// The state is left untouched when the layers do not apply.
+    fn apply_dataset(&mut self) -> Result<(), data::DataError> {
+        let layers = self
+            .layers
+            .iter()
+            .filter(|(_, active)| *active)
+            .map(|(layer, _)| layer)
+            .collect::<Vec<&data::Layer>>();
+        let monsters = data::apply_layers(&self.base_monsters, &layers, &self.data)?;
+        let data = data::GameData {
+            monsters,
+            ..(*self.data).clone()
+        };
+
+        // The embedded data is known to be consistent, only check what the user supplied
+        self.dataset_issues = match self.dataset_source.is_some() || !layers.is_empty() {
+            true => lint::lint_dataset(&data),
+            false => vec![],
+        };
+        self.tree_builder = recipe::MonsterTreeBuilder::new(data.monsters.clone());
+        self.configure_tree_builder();
+        self.data = Rc::new(data);
+        self.dataset_error = None;
+        if let Some(id) = self.search_condition.monster_id {
+            if !self.data.monsters.contains_key(&id) {
+                self.search_condition.monster_id = None;
+            }
         }
+        Ok(())
     }
 }
 
+/// Defaults to Japanese.
+fn browser_locale() -> data::Locale {
+    web_sys::window()
+        .and_then(|w| w.navigator().language())
+        .and_then(|lang| {
+            let code = lang.split('-').next().unwrap_or_default().to_lowercase();
+            data::Locale::from_code(&code)
+        })
+        .unwrap_or_default()
+}
+
 fn main() {
     wasm_logger::init(wasm_logger::Config::default());
     yew::Renderer::<App>::new().render();
 }
+
+#[test]
+fn test_family_classes() {
+    let data = data::GameData::embedded().unwrap();
+    let css = include_str!("../public/main.css");
+    for &id in data.families.keys() {
+        let class = data.family_class(id);
+        assert!(css.contains(&format!(".{:} {{", class)), "{:}", class);
+    }
+    assert!(css.contains(".color-unknown {"));
+}
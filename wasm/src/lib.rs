@@ -0,0 +1,203 @@
+//! The recipe engine for other web pages, exported with wasm-bindgen.
+//!
+//! Build with `wasm-pack build wasm --target web` and import the functions from
+//! `pkg/dqm3_fusion_wasm.js`. They run against the embedded dataset and return plain objects:
+//!
+//! - `buildTree(id, options)`: the recipe tree, nested by `children`
+//! - `materials(id, options)`: the materials to prepare for the recipe tree
+//! - `searchMonsters(query, options)`: the monsters matching the query, best matches first
+//! - `fusionResult(a, b, options)`: the monster with a special recipe of `a` and `b`, or `null`.
+//!   Standard fusions are not predicted.
+//!
+//! `options` may be omitted. It takes `locale` (`"ja"` or `"en"`), `preferStandard`,
+//! `maxSize` (`"normal"`, `"large"` or `"giant"`) and `limit` for the search.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use dqm3_fusion_core::data::{BodySize, GameData, Locale, MonsterId};
+use dqm3_fusion_core::export::{
+    get_material_rows, get_monster_row, get_monster_rows, get_tree_row, MaterialRow, MonsterRow,
+    TreeNodeRow,
+};
+use dqm3_fusion_core::recipe::{fusion_results, MonsterNode, MonsterTreeBuilder};
+use dqm3_fusion_core::search::SearchIndex;
+
+const DEFAULT_SEARCH_LIMIT: usize = 10;
+
+#[derive(Deserialize, Default, Debug)]
+#[serde(rename_all = "camelCase", default)]
+struct Options {
+    locale: Option<String>,
+    prefer_standard: bool,
+    max_size: Option<BodySize>,
+    limit: Option<usize>,
+}
+
+impl Options {
+    fn locale(&self) -> Result<Locale, String> {
+        match &self.locale {
+            Some(code) => Locale::from_code(code).ok_or(format!("unknown locale \"{:}\"", code)),
+            None => Ok(Locale::default()),
+        }
+    }
+}
+
+struct Engine {
+    data: GameData,
+    index: SearchIndex,
+}
+
+impl Engine {
+    fn new() -> Self {
+        let data = GameData::embedded().unwrap().clone();
+        Self {
+            index: SearchIndex::new(&data.monsters),
+            data,
+        }
+    }
+
+    fn monster(&self, id: usize) -> Result<MonsterId, String> {
+        Some(MonsterId(id))
+            .filter(|id| self.data.monsters.contains_key(id))
+            .ok_or(format!("no monster with id {:}", id))
+    }
+
+    fn build(&self, id: usize, options: &Options) -> Result<Rc<RefCell<MonsterNode>>, String> {
+        let mut tree_builder = MonsterTreeBuilder::new(self.data.monsters.clone());
+        tree_builder.prefer_standard_fusion(options.prefer_standard);
+        tree_builder.limit_size(options.max_size);
+        Ok(Rc::new(RefCell::new(tree_builder.build(self.monster(id)?))))
+    }
+
+    fn build_tree(&self, id: usize, options: &Options) -> Result<TreeNodeRow, String> {
+        let tree = self.build(id, options)?;
+        Ok(get_tree_row(tree, &self.data, options.locale()?))
+    }
+
+    fn materials(&self, id: usize, options: &Options) -> Result<Vec<MaterialRow>, String> {
+        let tree = self.build(id, options)?;
+        Ok(get_material_rows(tree, &self.data, options.locale()?))
+    }
+
+    fn search_monsters(&self, query: &str, options: &Options) -> Result<Vec<MonsterRow>, String> {
+        let ids = self
+            .index
+            .search(query, options.limit.unwrap_or(DEFAULT_SEARCH_LIMIT))
+            .into_iter()
+            .map(|hit| hit.monster)
+            .collect::<Vec<MonsterId>>();
+        Ok(get_monster_rows(&ids, &self.data, options.locale()?))
+    }
+
+    fn fusion_result(
+        &self,
+        a: usize,
+        b: usize,
+        options: &Options,
+    ) -> Result<Option<MonsterRow>, String> {
+        let locale = options.locale()?;
+        let results = fusion_results(&self.data.monsters, self.monster(a)?, self.monster(b)?);
+        Ok(results
+            .first()
+            .and_then(|&id| get_monster_row(id, &self.data, locale)))
+    }
+}
+
+thread_local! {
+    static ENGINE: Engine = Engine::new();
+}
+
+fn parse_options(options: JsValue) -> Result<Options, JsError> {
+    match options.is_undefined() || options.is_null() {
+        true => Ok(Options::default()),
+        false => serde_wasm_bindgen::from_value(options).map_err(|e| JsError::new(&e.to_string())),
+    }
+}
+
+fn to_js<T: Serialize>(result: Result<T, String>) -> Result<JsValue, JsError> {
+    let value = result.map_err(|e| JsError::new(&e))?;
+    value
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .map_err(|e| JsError::new(&e.to_string()))
+}
+
+#[wasm_bindgen(js_name = buildTree)]
+pub fn build_tree(id: usize, options: JsValue) -> Result<JsValue, JsError> {
+    let options = parse_options(options)?;
+    ENGINE.with(|engine| to_js(engine.build_tree(id, &options)))
+}
+
+#[wasm_bindgen]
+pub fn materials(id: usize, options: JsValue) -> Result<JsValue, JsError> {
+    let options = parse_options(options)?;
+    ENGINE.with(|engine| to_js(engine.materials(id, &options)))
+}
+
+#[wasm_bindgen(js_name = searchMonsters)]
+pub fn search_monsters(query: &str, options: JsValue) -> Result<JsValue, JsError> {
+    let options = parse_options(options)?;
+    ENGINE.with(|engine| to_js(engine.search_monsters(query, &options)))
+}
+
+#[wasm_bindgen(js_name = fusionResult)]
+pub fn fusion_result(a: usize, b: usize, options: JsValue) -> Result<JsValue, JsError> {
+    let options = parse_options(options)?;
+    ENGINE.with(|engine| to_js(engine.fusion_result(a, b, &options)))
+}
+
+#[test]
+fn test_build_tree() {
+    let engine = Engine::new();
+    let id = engine.index.resolve("キングスライム").unwrap().0;
+    let tree = engine.build_tree(id, &Options::default()).unwrap();
+    assert_eq!(tree.id, Some(MonsterId(id)));
+    assert_eq!(tree.name, "キングスライム");
+
+    let options = Options {
+        locale: Some("en".to_string()),
+        ..Default::default()
+    };
+    let materials = engine.materials(id, &options).unwrap();
+    assert!(materials.iter().all(|m| m.count > 0));
+    assert_eq!(
+        engine.build_tree(9999, &options),
+        Err("no monster with id 9999".to_string())
+    );
+}
+
+#[test]
+fn test_search_monsters() {
+    let engine = Engine::new();
+    let options = Options {
+        limit: Some(2),
+        ..Default::default()
+    };
+    let rows = engine.search_monsters("すらいむ", &options).unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].name, "スライム");
+
+    let options = Options {
+        locale: Some("fr".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(
+        engine.search_monsters("すらいむ", &options),
+        Err("unknown locale \"fr\"".to_string())
+    );
+}
+
+#[test]
+fn test_fusion_result() {
+    let engine = Engine::new();
+    let slime = engine.index.resolve("スライム").unwrap().0;
+    let mera_ghost = engine.index.resolve("メラゴースト").unwrap().0;
+    let result = engine
+        .fusion_result(slime, mera_ghost, &Options::default())
+        .unwrap()
+        .unwrap();
+    assert_eq!(result.name, "スライムベス");
+}